@@ -12,7 +12,9 @@ with open(log_filename) as logfile:
 
     index = 0
     for case in cases:
-        data = csv.reader(case.split('\n'))
+        # Lines starting with `#` are comments (e.g. calibrated temperatures).
+        rows = [line for line in case.split('\n') if not line.startswith('#')]
+        data = csv.reader(rows)
         for row in data:
             if not row == []:
                 indices.append(int(row[0]))
@@ -1,8 +1,10 @@
-use crate::{greedy, random};
-use crate::serializer::Serializer;
-use crate::simulated_annealing::{Reduction, SimulatedAnnealingParams, Solution};
+use crate::greedy;
+use crate::observers::Observers;
+use crate::pretty_print::{Printer, TerminalGantt};
+use crate::serializer::{self, Header, JsonLines, LogFormat, Serializer};
+use crate::solver::{self, Budget, Observer};
 use crate::utils::{Case, Schedule, Settings};
-use std::{error::Error, fs::{self, OpenOptions}, path::Path};
+use std::{collections::BTreeMap, error::Error, fs::{self, OpenOptions}, path::{Path, PathBuf}, time::Duration};
 pub struct App {}
 
 impl App {
@@ -11,30 +13,160 @@ impl App {
         fs::create_dir_all(Path::new(&settings.log_file).parent().unwrap_or(Path::new("./logs")))?;
         let log_file = OpenOptions::new()
             .create(true)
-            .write(true)
             .append(true)
             .open(&settings.log_file)?;
-        let mut serializer = Serializer::new(log_file);
-        serializer.buffered(!settings.unbuffered);
+        let mut log: Box<dyn Observer> = match settings.log_format {
+            LogFormat::Csv => {
+                let mut serializer = Serializer::new(log_file);
+                serializer.buffered(!settings.unbuffered);
+                Box::new(serializer)
+            }
+            LogFormat::Jsonl => {
+                let mut json = JsonLines::new(log_file);
+                json.buffered(!settings.unbuffered);
+                json.add_header(&Self::header(&settings, file)?)?;
+                Box::new(json)
+            }
+        };
+
+        let case    = Case::read_in_format(file, settings.case_format)?;
 
-        let case    = Case::read_from_file(file)?;
+        // Seed is recorded, so the run can be repeated.
+        println!("Seed: {}", settings.seed);
+        log.comment(&format!("seed {}", settings.seed));
+        if let Some(path) = &settings.resume {
+            println!("Resuming from checkpoint {}, seed is taken from it", path);
+        }
         let greed   = greedy::schedule(&case.clone());
-        let initial = random::schedule(&case);
 
         println!("Greedy solution: {}", greed.makespan().unwrap());
-        let params = SimulatedAnnealingParams {
-            initial_solution: initial,
-            initial_temperature: 75.0,
-            final_temperature: 0.01,
-            reduction_rule: Reduction::Geometric(0.995),
-            iterations_per_temperature: 20,
-            max_simulation_time: 120,
-            //max_simulation_time: settings.kill_time,
-            max_changeless_iterations: 12000,
+
+        let solver = solver::create(&settings.algorithm, &settings)?;
+        let mut budget = Budget::with_time(Duration::from_secs(settings.kill_time.into()));
+        if let Some(cpu_time) = settings.cpu_time {
+            budget = budget.with_cpu_time(cpu_time);
+        }
+        if let Some(iterations) = settings.max_iterations {
+            budget = budget.with_iterations(iterations);
+        }
+        if let Some(evaluations) = settings.max_evaluations {
+            budget = budget.with_evaluations(evaluations);
+        }
+
+        let initial = match &settings.initial {
+            Some(path) => {
+                let schedule = Schedule::read_from_file(path.as_str())?;
+                schedule
+                    .validate(&case)
+                    .map_err(|err| format!("Initial schedule {} doesn't fit case. {}", path, err))?;
+                println!("Initial solution: {}", schedule.makespan()?);
+                Some(schedule)
+            }
+            None => None,
         };
 
-        let solution = Solution::new(params).run(&mut serializer);
+        let gantt = TerminalGantt::detect().with_labels(settings.task_labels);
+        let mut printer = Printer::new(settings.verbosity).with_gantt(gantt);
+        let mut observers = Observers::new().with(&mut *log).with(&mut printer);
+        let outcome = solver.solve(&case, initial, &budget, &mut observers);
+        for line in &outcome.stats.details {
+            println!("{}", line);
+        }
+        if let Some(termination) = outcome.stats.termination {
+            println!("Stopped by {}", termination);
+        }
+        if settings.verbosity >= 1 {
+            print!("{}", gantt.render(&outcome.schedule));
+        }
+        if outcome.stats.optimal {
+            println!("Proven optimal: makespan equals lower bound {}", case.lower_bound());
+        }
+
+        if let Some(path) = Self::output_path(&settings, file) {
+            outcome.schedule.save_to_file(&path, settings.format, settings.seed)?;
+            println!("Schedule written to {}", path.display());
+        }
+
+        Ok(outcome.schedule)
+    }
+
+    /// Describes run of case read from `file` for JSON Lines log.
+    fn header(settings: &Settings, file: &str) -> Result<Header, Box<dyn Error>> {
+        let mut parameters = BTreeMap::new();
+        let mut set = |name: &str, value: String| {
+            parameters.insert(name.to_string(), value);
+        };
+        set("time_limit", settings.kill_time.to_string());
+        if let Some(cpu_time) = settings.cpu_time {
+            set("cpu_time", cpu_time.as_secs_f64().to_string());
+        }
+        if let Some(iterations) = settings.max_iterations {
+            set("max_iterations", iterations.to_string());
+        }
+        if let Some(evaluations) = settings.max_evaluations {
+            set("max_evaluations", evaluations.to_string());
+        }
+        set("runs", settings.runs.to_string());
+        if !settings.starts.is_empty() {
+            let starts: Vec<String> = settings.starts.iter().map(ToString::to_string).collect();
+            set("starts", starts.join(","));
+        }
+        if !settings.portfolio.is_empty() {
+            set("portfolio", settings.portfolio.join(","));
+        }
+        if let Some(calibration) = &settings.annealing.calibration {
+            set("calibration", calibration.to_string());
+        }
+        if let Some(policy) = settings.annealing.restart_policy() {
+            set("restart", format!("{} after {}", policy.strategy, policy.stagnation));
+        }
+        let annealing = &settings.annealing;
+        if let Some(temperature) = annealing.initial_temperature {
+            set("initial_temperature", temperature.to_string());
+        }
+        if let Some(temperature) = annealing.final_temperature {
+            set("final_temperature", temperature.to_string());
+        }
+        if let Some(cooling) = &annealing.cooling {
+            set("cooling", cooling.to_string());
+        }
+        if let Some(operators) = &annealing.operators {
+            set("operators", operators.to_string());
+        }
+        if let Some(acceptance) = &annealing.acceptance {
+            set("acceptance", acceptance.to_string());
+        }
+        if let Some(iterations) = annealing.iterations_per_temperature {
+            set("iterations_per_temperature", iterations.to_string());
+        }
+        if let Some(iterations) = annealing.max_changeless_iterations {
+            set("max_changeless_iterations", iterations.to_string());
+        }
+        if let Some(path) = &settings.initial {
+            set("initial", path.clone());
+        }
+        if let Some(path) = &settings.resume {
+            set("resume", path.clone());
+        }
+
+        Ok(Header {
+            instance: file.to_string(),
+            hash: serializer::fingerprint(&fs::read(file)?),
+            solver: settings.algorithm.clone(),
+            parameters,
+            seed: settings.seed,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        })
+    }
 
-        Ok(solution)
+    /// Returns path to which schedule of case read from `file` is written, if any.
+    fn output_path(settings: &Settings, file: &str) -> Option<PathBuf> {
+        let output = Path::new(settings.output.as_ref()?);
+        if settings.input_files.len() > 1 || settings.prompt {
+            let name = Path::new(file).file_name().unwrap_or_default().to_string_lossy();
+            Some(output.join(format!("{}.schedule", name)))
+        } else {
+            Some(output.to_path_buf())
+        }
     }
 }
@@ -0,0 +1,741 @@
+use crate::io::ScheduleFormat;
+use crate::multistart::InitialSolution;
+use crate::serializer::LogFormat;
+use crate::simulated_annealing::{
+    Acceptance, Calibration, Neighbourhood, Reduction, Restart, RestartPolicy, SimulatedAnnealingParams,
+};
+use std::{convert::TryFrom, error::Error, fmt, fs, str::FromStr};
+use yaml_rust::{Yaml, YamlLoader};
+
+/// Prefix of environment variables overriding configuration,
+/// e.g. `PCMAX_LIMITS_TIME` overrides `limits.time` and `PCMAX_ANNEALING_COOLING` overrides `annealing.cooling`.
+pub const ENV_PREFIX: &str = "PCMAX_";
+
+/// Environment variable naming configuration file used when `--config` isn't given.
+pub const ENV_CONFIG: &str = "PCMAX_CONFIG";
+
+/// Iterations without improvement after which annealing is restarted, unless configured otherwise.
+pub const DEFAULT_RESTART_AFTER: u64 = 2000;
+
+/// Keys of application settings, as written in configuration file.
+const KEYS: [&str; 18] = [
+    "log.file",
+    "log.format",
+    "log.unbuffered",
+    "verbosity",
+    "limits.time",
+    "limits.cpu_time",
+    "limits.iterations",
+    "limits.evaluations",
+    "solver.algorithm",
+    "solver.portfolio",
+    "solver.runs",
+    "solver.starts",
+    "solver.seed",
+    "solver.initial",
+    "checkpoint.file",
+    "checkpoint.every",
+    "checkpoint.resume",
+    "output.format",
+];
+
+/// Parameters of `annealing` section, as written in configuration file.
+const ANNEALING_KEYS: [&str; 11] = [
+    "initial_temperature",
+    "final_temperature",
+    "cooling",
+    "operators",
+    "acceptance",
+    "iterations_per_temperature",
+    "max_changeless_iterations",
+    "calibration",
+    "restart",
+    "restart_after",
+    "max_restarts",
+];
+
+/// Application settings layered from defaults, configuration file, environment variables
+/// and command line flags, every layer overriding the previous ones.
+///
+/// # Example
+///
+/// ```yaml
+/// log:
+///   file: ./logs/data.log
+///   format: jsonl
+///   unbuffered: false
+/// verbosity: 1
+/// limits:
+///   time: 120
+///   cpu_time: 60
+///   iterations: 1000000
+/// solver:
+///   algorithm: multistart
+///   runs: 4
+///   starts: [random, lpt]
+/// checkpoint:
+///   file: ./logs/checkpoint.json
+///   every: 30
+/// output:
+///   format: text
+/// annealing:
+///   cooling: geometric:0.995
+///   calibration: 0.8,0.001
+///   restart: reheat:0.5
+/// ```
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Config {
+    pub log_file: Option<String>,
+    pub log_format: Option<LogFormat>,
+    pub unbuffered: Option<bool>,
+    pub verbosity: Option<u8>,
+    /// Wall-clock time limit, in seconds.
+    pub time_limit: Option<u16>,
+    /// CPU time limit, in seconds.
+    pub cpu_time: Option<f64>,
+    pub max_iterations: Option<u64>,
+    pub max_evaluations: Option<u64>,
+    pub algorithm: Option<String>,
+    pub portfolio: Option<Vec<String>>,
+    pub runs: Option<usize>,
+    pub starts: Option<Vec<InitialSolution>>,
+    pub seed: Option<u64>,
+    /// Schedule file from which iterative solvers start.
+    pub initial: Option<String>,
+    /// File to which annealing periodically saves its state.
+    pub checkpoint: Option<String>,
+    /// Minimal time between two checkpoints, in seconds.
+    pub checkpoint_every: Option<f64>,
+    /// Checkpoint file from which annealing continues.
+    pub resume: Option<String>,
+    pub format: Option<ScheduleFormat>,
+    pub annealing: AnnealingConfig,
+}
+
+impl Config {
+    /// Returns built-in settings, the lowest layer of configuration.
+    pub fn defaults() -> Self {
+        Self {
+            log_file: Some("./logs/data.log".to_string()),
+            log_format: Some(LogFormat::Csv),
+            unbuffered: Some(false),
+            verbosity: Some(0),
+            time_limit: Some(120),
+            algorithm: Some("sa".to_string()),
+            runs: Some(1),
+            checkpoint_every: Some(60.0),
+            format: Some(ScheduleFormat::Json),
+            annealing: AnnealingConfig::defaults(),
+            ..Self::default()
+        }
+    }
+
+    /// Parses YAML configuration, see [`Config`] for its layout.
+    pub fn from_yaml(source: &str) -> Result<Self, String> {
+        let documents = YamlLoader::load_from_str(source).map_err(|err| err.to_string())?;
+        let mut config = Self {
+            annealing: AnnealingConfig::from_yaml(source)?,
+            ..Self::default()
+        };
+
+        let document = match documents.first() {
+            Some(Yaml::Hash(document)) => document,
+            Some(Yaml::Null) | None => return Ok(config),
+            Some(_) => return Err("Configuration has to be a mapping.".to_string()),
+        };
+
+        for (key, value) in document {
+            let key = key.as_str().ok_or("Configuration keys have to be strings.")?;
+            match (key, value) {
+                ("annealing", _) => {}
+                ("log", Yaml::Hash(section))
+                | ("limits", Yaml::Hash(section))
+                | ("solver", Yaml::Hash(section))
+                | ("checkpoint", Yaml::Hash(section))
+                | ("output", Yaml::Hash(section)) => {
+                    for (name, value) in section {
+                        let name = name.as_str().ok_or("Configuration keys have to be strings.")?;
+                        let key = format!("{}.{}", key, name);
+                        config.set(&key, &scalar(&key, value)?)?;
+                    }
+                }
+                ("log", _) | ("limits", _) | ("solver", _) | ("checkpoint", _) | ("output", _) => {
+                    return Err(format!("`{}` has to be a mapping.", key))
+                }
+                _ => config.set(key, &scalar(key, value)?)?,
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn read_from_file<P: Into<String>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let source = fs::read_to_string(path.into())?;
+        Ok(Self::from_yaml(&source)?)
+    }
+
+    /// Reads settings from `PCMAX_*` variables among `variables`,
+    /// named after configuration keys, e.g. `PCMAX_SOLVER_RUNS=4`.
+    /// Lists are separated by commas.
+    ///
+    /// Unknown `PCMAX_*` variables are skipped with a warning,
+    /// they may belong to other tools or other versions.
+    pub fn from_env<I: IntoIterator<Item = (String, String)>>(variables: I) -> Result<Self, String> {
+        let mut config = Self::default();
+        for (name, value) in variables {
+            if name == ENV_CONFIG || !name.starts_with(ENV_PREFIX) {
+                continue;
+            }
+            let key = KEYS.iter().find(|key| env_name(key) == name);
+            let parameter = ANNEALING_KEYS
+                .iter()
+                .find(|parameter| env_name(&format!("annealing.{}", parameter)) == name);
+            match (key, parameter) {
+                (Some(key), _) => config.set(key, &value)?,
+                (None, Some(parameter)) => config.annealing.set(parameter, &value)?,
+                (None, None) => eprintln!("Warning: ignoring unknown environment variable `{}`.", name),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Sets setting of `key` (as listed in configuration file, e.g. `limits.time`) to parsed `value`.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "log.file" => self.log_file = Some(value.to_string()),
+            "log.format" => self.log_format = Some(value.parse()?),
+            "log.unbuffered" => self.unbuffered = Some(parse(key, value)?),
+            "verbosity" => self.verbosity = Some(parse(key, value)?),
+            "limits.time" => self.time_limit = Some(parse(key, value)?),
+            "limits.cpu_time" => self.cpu_time = Some(parse(key, value)?),
+            "limits.iterations" => self.max_iterations = Some(parse(key, value)?),
+            "limits.evaluations" => self.max_evaluations = Some(parse(key, value)?),
+            "solver.algorithm" => self.algorithm = Some(value.to_string()),
+            "solver.portfolio" => self.portfolio = Some(list(value).map(ToString::to_string).collect()),
+            "solver.runs" => self.runs = Some(parse(key, value)?),
+            "solver.starts" => {
+                self.starts = Some(list(value).map(str::parse).collect::<Result<_, _>>()?);
+            }
+            "solver.seed" => self.seed = Some(parse(key, value)?),
+            "solver.initial" => self.initial = Some(value.to_string()),
+            "checkpoint.file" => self.checkpoint = Some(value.to_string()),
+            "checkpoint.every" => self.checkpoint_every = Some(parse(key, value)?),
+            "checkpoint.resume" => self.resume = Some(value.to_string()),
+            "output.format" => self.format = Some(value.parse()?),
+            _ => return Err(format!("Unknown setting `{}`.", key)),
+        }
+        Ok(())
+    }
+
+    /// Returns configuration in which settings given in `other` override these ones.
+    pub fn merge(self, other: Config) -> Self {
+        Self {
+            log_file: other.log_file.or(self.log_file),
+            log_format: other.log_format.or(self.log_format),
+            unbuffered: other.unbuffered.or(self.unbuffered),
+            verbosity: other.verbosity.or(self.verbosity),
+            time_limit: other.time_limit.or(self.time_limit),
+            cpu_time: other.cpu_time.or(self.cpu_time),
+            max_iterations: other.max_iterations.or(self.max_iterations),
+            max_evaluations: other.max_evaluations.or(self.max_evaluations),
+            algorithm: other.algorithm.or(self.algorithm),
+            portfolio: other.portfolio.or(self.portfolio),
+            runs: other.runs.or(self.runs),
+            starts: other.starts.or(self.starts),
+            seed: other.seed.or(self.seed),
+            initial: other.initial.or(self.initial),
+            checkpoint: other.checkpoint.or(self.checkpoint),
+            checkpoint_every: other.checkpoint_every.or(self.checkpoint_every),
+            resume: other.resume.or(self.resume),
+            format: other.format.or(self.format),
+            annealing: self.annealing.merge(other.annealing),
+        }
+    }
+}
+
+/// Writes configuration as YAML readable by `from_yaml`, omitting settings which weren't given.
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let joined = |values: Vec<String>| format!("[{}]", values.join(", "));
+        let entries: Vec<(&str, Option<String>)> = vec![
+            ("log.file", self.log_file.as_ref().map(|file| format!("\"{}\"", file))),
+            ("log.format", self.log_format.map(|format| format.to_string())),
+            ("log.unbuffered", self.unbuffered.map(|unbuffered| unbuffered.to_string())),
+            ("verbosity", self.verbosity.map(|verbosity| verbosity.to_string())),
+            ("limits.time", self.time_limit.map(|time| time.to_string())),
+            ("limits.cpu_time", self.cpu_time.map(|time| time.to_string())),
+            ("limits.iterations", self.max_iterations.map(|count| count.to_string())),
+            ("limits.evaluations", self.max_evaluations.map(|count| count.to_string())),
+            ("solver.algorithm", self.algorithm.clone()),
+            ("solver.portfolio", self.portfolio.clone().map(joined)),
+            ("solver.runs", self.runs.map(|runs| runs.to_string())),
+            (
+                "solver.starts",
+                self.starts.as_ref().map(|starts| joined(starts.iter().map(ToString::to_string).collect())),
+            ),
+            ("solver.seed", self.seed.map(|seed| seed.to_string())),
+            ("solver.initial", self.initial.as_ref().map(|file| format!("\"{}\"", file))),
+            ("checkpoint.file", self.checkpoint.as_ref().map(|file| format!("\"{}\"", file))),
+            ("checkpoint.every", self.checkpoint_every.map(|every| every.to_string())),
+            ("checkpoint.resume", self.resume.as_ref().map(|file| format!("\"{}\"", file))),
+            ("output.format", self.format.map(|format| format.to_string())),
+        ];
+
+        let mut section = None;
+        for (key, value) in entries {
+            let value = match value {
+                Some(value) => value,
+                None => continue,
+            };
+            match key.split_once('.') {
+                Some((name, key)) => {
+                    if section != Some(name) {
+                        writeln!(f, "{}:", name)?;
+                        section = Some(name);
+                    }
+                    writeln!(f, "  {}: {}", key, value)?;
+                }
+                None => {
+                    section = None;
+                    writeln!(f, "{}: {}", key, value)?;
+                }
+            }
+        }
+        if self.annealing != AnnealingConfig::default() {
+            write!(f, "{}", self.annealing)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns name of environment variable overriding setting of `key`.
+fn env_name(key: &str) -> String {
+    format!("{}{}", ENV_PREFIX, key.replace('.', "_").to_uppercase())
+}
+
+fn list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
+
+fn parse<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid value `{}` of `{}`.", value, key))
+}
+
+/// Returns YAML scalar (or list of scalars, joined with commas) as string.
+fn scalar(key: &str, value: &Yaml) -> Result<String, String> {
+    match value {
+        Yaml::String(value) | Yaml::Real(value) => Ok(value.clone()),
+        Yaml::Integer(value) => Ok(value.to_string()),
+        Yaml::Boolean(value) => Ok(value.to_string()),
+        Yaml::Array(values) => Ok(values
+            .iter()
+            .map(|value| scalar(key, value))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(",")),
+        _ => Err(format!("`{}` has to be a value or list of values.", key)),
+    }
+}
+
+/// Simulated Annealing parameters read from configuration file.
+/// Parameters which weren't given keep their default values.
+///
+/// # Example
+///
+/// ```yaml
+/// annealing:
+///   initial_temperature: 75
+///   final_temperature: 0.01
+///   cooling: geometric:0.995
+///   operators: biased:0.6=3,swap,ejection:3
+///   acceptance: metropolis
+///   iterations_per_temperature: 20
+///   max_changeless_iterations: 12000
+///   calibration: 0.8,0.001
+///   restart: best
+///   restart_after: 2000
+///   max_restarts: 5
+/// ```
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct AnnealingConfig {
+    pub initial_temperature: Option<f64>,
+    pub final_temperature: Option<f64>,
+    pub cooling: Option<Reduction>,
+    pub operators: Option<Neighbourhood>,
+    pub acceptance: Option<Acceptance>,
+    pub iterations_per_temperature: Option<u16>,
+    pub max_changeless_iterations: Option<u16>,
+    /// Calibrates temperatures to acceptance probabilities instead of using configured ones.
+    pub calibration: Option<Calibration>,
+    /// Restarts stagnating annealing.
+    pub restart: Option<Restart>,
+    /// Iterations without improvement after which annealing is restarted,
+    /// [`DEFAULT_RESTART_AFTER`] when not given.
+    pub restart_after: Option<u64>,
+    /// Limits number of restarts, unlimited when not given.
+    pub max_restarts: Option<u32>,
+}
+
+impl AnnealingConfig {
+    /// Parses `annealing` section of YAML configuration.
+    pub fn from_yaml(source: &str) -> Result<Self, String> {
+        let documents = YamlLoader::load_from_str(source).map_err(|err| err.to_string())?;
+        let mut config = Self::default();
+
+        let section = match documents.first().map(|document| &document["annealing"]) {
+            Some(Yaml::Hash(section)) => section,
+            Some(Yaml::BadValue) | None => return Ok(config),
+            Some(_) => return Err("`annealing` has to be a mapping.".to_string()),
+        };
+
+        for (key, value) in section {
+            let key = key.as_str().ok_or("Configuration keys have to be strings.")?;
+            match key {
+                "initial_temperature" => config.initial_temperature = Some(number(key, value)?),
+                "final_temperature" => config.final_temperature = Some(number(key, value)?),
+                "cooling" => {
+                    let cooling = value.as_str().ok_or("`cooling` has to be a string, e.g. `geometric:0.995`.")?;
+                    config.cooling = Some(cooling.parse()?);
+                }
+                "operators" => {
+                    let operators = value
+                        .as_str()
+                        .ok_or("`operators` has to be a string, e.g. `biased:0.6=3,swap,ejection:3`.")?;
+                    config.operators = Some(operators.parse()?);
+                }
+                "acceptance" => {
+                    let acceptance = value.as_str().ok_or("`acceptance` has to be a string, e.g. `late:500`.")?;
+                    config.acceptance = Some(acceptance.parse()?);
+                }
+                "iterations_per_temperature" => config.iterations_per_temperature = Some(integer(key, value)?),
+                "max_changeless_iterations" => config.max_changeless_iterations = Some(integer(key, value)?),
+                "calibration" => config.calibration = Some(scalar(key, value)?.parse()?),
+                "restart" => {
+                    let restart = value.as_str().ok_or("`restart` has to be a string, e.g. `reheat:0.5`.")?;
+                    config.restart = Some(restart.parse()?);
+                }
+                "restart_after" => config.restart_after = Some(integer(key, value)?),
+                "max_restarts" => config.max_restarts = Some(integer(key, value)?),
+                _ => return Err(format!("Unknown annealing parameter `{}`.", key)),
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn read_from_file<P: Into<String>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let source = fs::read_to_string(path.into())?;
+        Ok(Self::from_yaml(&source)?)
+    }
+
+    /// Returns default parameters of Simulated Annealing.
+    pub fn defaults() -> Self {
+        let params = SimulatedAnnealingParams::default();
+        Self {
+            initial_temperature: Some(params.initial_temperature),
+            final_temperature: Some(params.final_temperature),
+            cooling: Some(params.reduction_rule),
+            operators: Some(params.neighbourhood),
+            acceptance: Some(params.acceptance),
+            iterations_per_temperature: Some(params.iterations_per_temperature),
+            max_changeless_iterations: Some(params.max_changeless_iterations),
+            ..Self::default()
+        }
+    }
+
+    /// Sets parameter `key` (as named in `annealing` section) to parsed `value`.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "initial_temperature" => self.initial_temperature = Some(parse(key, value)?),
+            "final_temperature" => self.final_temperature = Some(parse(key, value)?),
+            "cooling" => self.cooling = Some(value.parse()?),
+            "operators" => self.operators = Some(value.parse()?),
+            "acceptance" => self.acceptance = Some(value.parse()?),
+            "iterations_per_temperature" => self.iterations_per_temperature = Some(parse(key, value)?),
+            "max_changeless_iterations" => self.max_changeless_iterations = Some(parse(key, value)?),
+            "calibration" => self.calibration = Some(value.parse()?),
+            "restart" => self.restart = Some(value.parse()?),
+            "restart_after" => self.restart_after = Some(parse(key, value)?),
+            "max_restarts" => self.max_restarts = Some(parse(key, value)?),
+            _ => return Err(format!("Unknown annealing parameter `{}`.", key)),
+        }
+        Ok(())
+    }
+
+    /// Returns configuration in which parameters given in `other` override these ones.
+    pub fn merge(self, other: AnnealingConfig) -> Self {
+        Self {
+            initial_temperature: other.initial_temperature.or(self.initial_temperature),
+            final_temperature: other.final_temperature.or(self.final_temperature),
+            cooling: other.cooling.or(self.cooling),
+            operators: other.operators.or(self.operators),
+            acceptance: other.acceptance.or(self.acceptance),
+            iterations_per_temperature: other.iterations_per_temperature.or(self.iterations_per_temperature),
+            max_changeless_iterations: other.max_changeless_iterations.or(self.max_changeless_iterations),
+            calibration: other.calibration.or(self.calibration),
+            restart: other.restart.or(self.restart),
+            restart_after: other.restart_after.or(self.restart_after),
+            max_restarts: other.max_restarts.or(self.max_restarts),
+        }
+    }
+
+    /// Returns restart policy made of configured strategy, stagnation and limit,
+    /// `None` when no strategy is configured.
+    pub fn restart_policy(&self) -> Option<RestartPolicy> {
+        self.restart.map(|strategy| {
+            let policy = RestartPolicy::new(strategy, self.restart_after.unwrap_or(DEFAULT_RESTART_AFTER));
+            match self.max_restarts {
+                Some(max) => policy.with_max_restarts(max),
+                None => policy,
+            }
+        })
+    }
+
+    /// Overrides `params` with configured parameters.
+    pub fn apply(&self, params: SimulatedAnnealingParams) -> SimulatedAnnealingParams {
+        SimulatedAnnealingParams {
+            initial_temperature: self.initial_temperature.unwrap_or(params.initial_temperature),
+            final_temperature: self.final_temperature.unwrap_or(params.final_temperature),
+            reduction_rule: self.cooling.clone().unwrap_or(params.reduction_rule.clone()),
+            neighbourhood: self.operators.clone().unwrap_or(params.neighbourhood.clone()),
+            acceptance: self.acceptance.unwrap_or(params.acceptance),
+            iterations_per_temperature: self.iterations_per_temperature.unwrap_or(params.iterations_per_temperature),
+            max_changeless_iterations: self.max_changeless_iterations.unwrap_or(params.max_changeless_iterations),
+            calibration: self.calibration.clone().or(params.calibration.clone()),
+            restart: self.restart_policy().or(params.restart.clone()),
+            ..params
+        }
+    }
+}
+
+/// Writes configuration as YAML readable by `from_yaml`, omitting parameters which weren't given.
+impl fmt::Display for AnnealingConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "annealing:")?;
+        if let Some(temperature) = self.initial_temperature {
+            writeln!(f, "  initial_temperature: {}", temperature)?;
+        }
+        if let Some(temperature) = self.final_temperature {
+            writeln!(f, "  final_temperature: {}", temperature)?;
+        }
+        if let Some(cooling) = &self.cooling {
+            writeln!(f, "  cooling: \"{}\"", cooling)?;
+        }
+        if let Some(operators) = &self.operators {
+            writeln!(f, "  operators: \"{}\"", operators)?;
+        }
+        if let Some(acceptance) = &self.acceptance {
+            writeln!(f, "  acceptance: \"{}\"", acceptance)?;
+        }
+        if let Some(iterations) = self.iterations_per_temperature {
+            writeln!(f, "  iterations_per_temperature: {}", iterations)?;
+        }
+        if let Some(iterations) = self.max_changeless_iterations {
+            writeln!(f, "  max_changeless_iterations: {}", iterations)?;
+        }
+        if let Some(calibration) = &self.calibration {
+            writeln!(f, "  calibration: \"{}\"", calibration)?;
+        }
+        if let Some(restart) = &self.restart {
+            writeln!(f, "  restart: \"{}\"", restart)?;
+        }
+        if let Some(iterations) = self.restart_after {
+            writeln!(f, "  restart_after: {}", iterations)?;
+        }
+        if let Some(restarts) = self.max_restarts {
+            writeln!(f, "  max_restarts: {}", restarts)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn number(key: &str, value: &Yaml) -> Result<f64, String> {
+    match value {
+        Yaml::Real(_) => value.as_f64(),
+        Yaml::Integer(value) => Some(*value as f64),
+        _ => None,
+    }
+    .ok_or_else(|| format!("`{}` has to be a number.", key))
+}
+
+pub(crate) fn integer<T: TryFrom<i64>>(key: &str, value: &Yaml) -> Result<T, String> {
+    value
+        .as_i64()
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or_else(|| format!("`{}` has to be a non-negative integer in range.", key))
+}
+
+#[cfg(test)]
+mod test_config {
+    use super::*;
+
+    #[test]
+    fn test_from_yaml() {
+        let config = AnnealingConfig::from_yaml(
+            "annealing:\n  initial_temperature: 40\n  cooling: lam:0.999\n  operators: swap=2,random\n  iterations_per_temperature: 50\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.initial_temperature, Some(40.0));
+        assert_eq!(config.final_temperature, None);
+        assert_eq!(config.cooling, Some(Reduction::Lam(0.999)));
+        assert_eq!(config.operators, "swap=2,random".parse().ok());
+        assert_eq!(config.iterations_per_temperature, Some(50));
+    }
+
+    #[test]
+    fn test_from_yaml_errors() {
+        assert!(AnnealingConfig::from_yaml("annealing:\n  cooling: cubic:2\n").is_err());
+        assert!(AnnealingConfig::from_yaml("annealing:\n  iterations_per_temperature: -1\n").is_err());
+        assert!(AnnealingConfig::from_yaml("annealing:\n  temperature: 5\n").is_err());
+        assert!(AnnealingConfig::from_yaml("annealing:\n  operators: swap=0\n").is_err());
+        assert!(AnnealingConfig::from_yaml("annealing:\n  acceptance: late:0\n").is_err());
+        assert_eq!(AnnealingConfig::from_yaml("other: 1\n"), Ok(AnnealingConfig::default()));
+    }
+
+    #[test]
+    fn test_merge_and_apply() {
+        let file = AnnealingConfig {
+            initial_temperature: Some(40.0),
+            cooling: Some(Reduction::Geometric(0.9)),
+            ..AnnealingConfig::default()
+        };
+        let cli = AnnealingConfig {
+            cooling: Some(Reduction::ExponentialTime),
+            acceptance: Some(Acceptance::Threshold),
+            ..AnnealingConfig::default()
+        };
+
+        let params = file.merge(cli).apply(SimulatedAnnealingParams::default());
+
+        assert_eq!(params.initial_temperature, 40.0);
+        assert_eq!(params.final_temperature, 0.01);
+        assert_eq!(params.reduction_rule, Reduction::ExponentialTime);
+        assert_eq!(params.acceptance, Acceptance::Threshold);
+    }
+
+    #[test]
+    fn test_display_reads_back() {
+        let config = AnnealingConfig {
+            initial_temperature: Some(12.5),
+            cooling: Some(Reduction::Geometric(0.99)),
+            operators: "biased:0.6=3,swap".parse().ok(),
+            iterations_per_temperature: Some(40),
+            ..AnnealingConfig::default()
+        };
+
+        assert_eq!(AnnealingConfig::from_yaml(&config.to_string()), Ok(config));
+    }
+
+    #[test]
+    fn test_config_from_yaml() {
+        let config = Config::from_yaml(
+            "verbosity: 2\nlimits:\n  time: 30\n  cpu_time: 1.5\nsolver:\n  starts: [lpt, random]\n  portfolio: [lpt, sa]\nannealing:\n  cooling: lam:0.99\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.verbosity, Some(2));
+        assert_eq!(config.time_limit, Some(30));
+        assert_eq!(config.cpu_time, Some(1.5));
+        assert_eq!(config.starts, Some(vec![InitialSolution::Lpt, InitialSolution::Random]));
+        assert_eq!(config.portfolio, Some(vec!["lpt".to_string(), "sa".to_string()]));
+        assert_eq!(config.annealing.cooling, Some(Reduction::Lam(0.99)));
+        assert_eq!(config.algorithm, None);
+
+        assert!(Config::from_yaml("limits:\n  time: -1\n").is_err());
+        assert!(Config::from_yaml("limits: 5\n").is_err());
+        assert!(Config::from_yaml("solver:\n  threads: 4\n").is_err());
+        assert!(Config::from_yaml("solver:\n  starts: [best]\n").is_err());
+    }
+
+    #[test]
+    fn test_layers() {
+        let file = Config::from_yaml("limits:\n  time: 30\nsolver:\n  runs: 2\n").unwrap();
+        let environment = Config::from_env(vec![
+            ("PCMAX_SOLVER_RUNS".to_string(), "4".to_string()),
+            ("PCMAX_ANNEALING_ITERATIONS_PER_TEMPERATURE".to_string(), "50".to_string()),
+            ("HOME".to_string(), "/root".to_string()),
+        ])
+        .unwrap();
+        let cli = Config {
+            time_limit: Some(10),
+            ..Config::default()
+        };
+
+        let config = Config::defaults().merge(file).merge(environment).merge(cli);
+
+        assert_eq!(config.time_limit, Some(10));
+        assert_eq!(config.runs, Some(4));
+        assert_eq!(config.algorithm, Some("sa".to_string()));
+        assert_eq!(config.annealing.iterations_per_temperature, Some(50));
+        assert_eq!(config.annealing.final_temperature, Some(0.01));
+
+        // Unknown variables are only warned about, invalid values of known ones are errors.
+        assert_eq!(
+            Config::from_env(vec![
+                ("PCMAX_SOLVER_THREADS".to_string(), "4".to_string()),
+                ("PCMAX_ANNEALING_TEMPERATURE".to_string(), "5".to_string()),
+            ]),
+            Ok(Config::default())
+        );
+        assert!(Config::from_env(vec![("PCMAX_ANNEALING_COOLING".to_string(), "cubic".to_string())]).is_err());
+    }
+
+    #[test]
+    fn test_restart_calibration_and_checkpoints() {
+        let file = Config::from_yaml(
+            "solver:\n  initial: start.json\ncheckpoint:\n  file: run.json\n  every: 2.5\nannealing:\n  calibration: 0.8\n  restart: best\n  max_restarts: 3\n",
+        )
+        .unwrap();
+        let environment = Config::from_env(vec![
+            ("PCMAX_ANNEALING_CALIBRATION".to_string(), "0.5,0.01".to_string()),
+            ("PCMAX_ANNEALING_RESTART_AFTER".to_string(), "300".to_string()),
+            ("PCMAX_CHECKPOINT_RESUME".to_string(), "old.json".to_string()),
+        ])
+        .unwrap();
+
+        let config = Config::defaults().merge(file).merge(environment);
+
+        assert_eq!(config.initial, Some("start.json".to_string()));
+        assert_eq!(config.checkpoint, Some("run.json".to_string()));
+        assert_eq!(config.checkpoint_every, Some(2.5));
+        assert_eq!(config.resume, Some("old.json".to_string()));
+        assert_eq!(config.annealing.calibration, "0.5,0.01".parse().ok());
+        assert_eq!(
+            config.annealing.restart_policy(),
+            Some(RestartPolicy::new(Restart::Best, 300).with_max_restarts(3))
+        );
+        let params = config.annealing.apply(SimulatedAnnealingParams::default());
+        assert_eq!(params.calibration, config.annealing.calibration);
+        assert_eq!(params.restart, config.annealing.restart_policy());
+
+        assert_eq!(Config::defaults().annealing.restart_policy(), None);
+        let restart = Config::from_yaml("annealing:\n  restart: reheat:0.5\n").unwrap();
+        assert_eq!(restart.annealing.restart_policy().unwrap().stagnation, DEFAULT_RESTART_AFTER);
+        assert!(Config::from_yaml("annealing:\n  calibration: 1.5\n").is_err());
+        assert!(Config::from_yaml("annealing:\n  restart: sometimes\n").is_err());
+        assert!(Config::from_yaml("checkpoint:\n  every: soon\n").is_err());
+    }
+
+    #[test]
+    fn test_config_display_reads_back() {
+        let config = Config {
+            max_evaluations: Some(1000),
+            portfolio: Some(vec!["lpt".to_string(), "sa".to_string()]),
+            starts: Some(vec![InitialSolution::PerturbedLpt]),
+            log_format: Some(LogFormat::Jsonl),
+            checkpoint: Some("./logs/checkpoint.json".to_string()),
+            annealing: AnnealingConfig {
+                calibration: "0.8,0.001".parse().ok(),
+                restart: Some(Restart::Reheat(0.5)),
+                max_restarts: Some(4),
+                ..AnnealingConfig::defaults()
+            },
+            ..Config::defaults()
+        };
+
+        assert_eq!(Config::from_yaml(&config.to_string()), Ok(config));
+    }
+}
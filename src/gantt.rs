@@ -0,0 +1,255 @@
+//! Gantt charts of schedules, written as SVG or self-contained HTML.
+
+use crate::plot::{decimals, escape, ticks};
+use crate::utils::Schedule;
+use std::{fmt, path::Path, str::FromStr};
+
+const WIDTH: f64 = 1000.0;
+const LABEL_WIDTH: f64 = 130.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 40.0;
+const ROW_HEIGHT: f64 = 28.0;
+const AXIS_HEIGHT: f64 = 30.0;
+/// Approximate width of label character, blocks too narrow for their label stay unlabeled.
+const CHARACTER_WIDTH: f64 = 7.0;
+const COLORS: [&str; 2] = ["#9acd32", "#ffd700"];
+const CRITICAL: &str = "#d62728";
+
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum GanttFormat {
+    #[default]
+    Svg,
+    /// HTML page embedding SVG chart, with tooltips shown on hover.
+    Html,
+}
+
+impl GanttFormat {
+    /// Guesses format from extension of `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        path.as_ref().extension()?.to_str()?.to_lowercase().parse().ok()
+    }
+}
+
+impl fmt::Display for GanttFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Svg => write!(f, "svg"),
+            Self::Html => write!(f, "html"),
+        }
+    }
+}
+
+impl FromStr for GanttFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "svg" => Ok(Self::Svg),
+            "html" | "htm" => Ok(Self::Html),
+            _ => Err(format!("Unknown Gantt chart format `{}`.", name)),
+        }
+    }
+}
+
+/// Gantt chart of schedule: row per core on time axis scaled to makespan,
+/// with cores finishing at makespan (critical ones) highlighted.
+pub struct Gantt<'a> {
+    schedule: &'a Schedule,
+    title: String,
+}
+
+impl<'a> Gantt<'a> {
+    pub fn new(schedule: &'a Schedule) -> Self {
+        Self {
+            schedule,
+            title: "Schedule".to_string(),
+        }
+    }
+
+    pub fn with_title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn render(&self, format: GanttFormat) -> String {
+        match format {
+            GanttFormat::Svg => self.to_svg(),
+            GanttFormat::Html => self.to_html(),
+        }
+    }
+
+    fn makespan(&self) -> u128 {
+        self.schedule.cores().iter().map(|core| core.working_time()).max().unwrap_or(0)
+    }
+
+    pub fn to_svg(&self) -> String {
+        let cores = self.schedule.cores();
+        let makespan = self.makespan();
+        let height = MARGIN_TOP + ROW_HEIGHT * cores.len() as f64 + AXIS_HEIGHT;
+        let chart_width = WIDTH - LABEL_WIDTH - MARGIN_RIGHT;
+        // Empty schedule still gets an axis.
+        let scale = chart_width / makespan.max(1) as f64;
+        let x = |time: u128| LABEL_WIDTH + time as f64 * scale;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n\
+             <rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n\
+             <text x=\"{cx}\" y=\"22\" text-anchor=\"middle\" font-size=\"15\">{title} (makespan {makespan})</text>\n",
+            w = WIDTH,
+            h = height,
+            cx = WIDTH / 2.0,
+            title = escape(&self.title),
+            makespan = makespan
+        );
+
+        let (ticks, _, _) = ticks(0.0, makespan.max(1) as f64);
+        let places = decimals(&ticks);
+        let bottom = MARGIN_TOP + ROW_HEIGHT * cores.len() as f64;
+        for tick in ticks.into_iter().filter(|&tick| tick <= makespan.max(1) as f64) {
+            let position = LABEL_WIDTH + tick * scale;
+            svg += &format!(
+                "<line x1=\"{x:.1}\" y1=\"{top}\" x2=\"{x:.1}\" y2=\"{bottom}\" stroke=\"#dddddd\"/>\n\
+                 <text x=\"{x:.1}\" y=\"{label}\" text-anchor=\"middle\">{tick:.places$}</text>\n",
+                x = position,
+                top = MARGIN_TOP,
+                bottom = bottom,
+                label = bottom + 16.0,
+                tick = tick,
+                places = places
+            );
+        }
+
+        let mut number = 0;
+        for (idx, core) in cores.iter().enumerate() {
+            let load = core.working_time();
+            let critical = load == makespan && makespan > 0;
+            let top = MARGIN_TOP + ROW_HEIGHT * idx as f64;
+            let middle = top + ROW_HEIGHT / 2.0;
+            svg += &format!(
+                "<g class=\"core{}\">\n\
+                 <text x=\"{}\" y=\"{:.1}\" dominant-baseline=\"middle\"{}>Core {} · {}</text>\n",
+                if critical { " critical" } else { "" },
+                8.0,
+                middle,
+                if critical {
+                    format!(" font-weight=\"bold\" fill=\"{}\"", CRITICAL)
+                } else {
+                    String::new()
+                },
+                idx + 1,
+                load
+            );
+
+            let mut start = 0u128;
+            for (position, task) in core.get_tasks().iter().enumerate() {
+                number += 1;
+                let end = start + u128::from(task.length());
+                let width = (end - start) as f64 * scale;
+                svg += &format!(
+                    "<rect class=\"task\" x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\" stroke=\"white\">\
+                     <title>Task t{}: core {}, position {}, length {}, from {} to {}</title></rect>\n",
+                    x(start),
+                    top + 3.0,
+                    width,
+                    ROW_HEIGHT - 6.0,
+                    COLORS[position % COLORS.len()],
+                    number,
+                    idx + 1,
+                    position + 1,
+                    task.length(),
+                    start,
+                    end
+                );
+                let label = format!("t{} ({})", number, task.length());
+                if label.len() as f64 * CHARACTER_WIDTH <= width {
+                    svg += &format!(
+                        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"middle\" pointer-events=\"none\">{}</text>\n",
+                        x(start) + width / 2.0,
+                        middle,
+                        label
+                    );
+                }
+                start = end;
+            }
+            if critical {
+                svg += &format!(
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+                    LABEL_WIDTH,
+                    top + 2.0,
+                    chart_width,
+                    ROW_HEIGHT - 4.0,
+                    CRITICAL
+                );
+            }
+            svg += "</g>\n";
+        }
+        svg + "</svg>\n"
+    }
+
+    /// Returns HTML page which needs no other files.
+    pub fn to_html(&self) -> String {
+        let cores = self.schedule.cores();
+        let tasks: usize = cores.iter().map(|core| core.get_tasks().len()).sum();
+        format!(
+            "<!DOCTYPE html>\n\
+             <html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\n\
+             body {{ font-family: sans-serif; margin: 2em; }}\n\
+             rect.task:hover {{ opacity: 0.6; cursor: default; }}\n\
+             </style>\n</head>\n<body>\n\
+             <h1>{title}</h1>\n\
+             <p>Makespan {makespan} on {cores} cores, {tasks} tasks. Critical cores are outlined, hover over task to see its details.</p>\n\
+             {svg}</body>\n</html>\n",
+            title = escape(&self.title),
+            makespan = self.makespan(),
+            cores = cores.len(),
+            tasks = tasks,
+            svg = self.to_svg()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_gantt {
+    use super::*;
+    use crate::utils::{Core, Task};
+
+    fn schedule() -> Schedule {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from(vec![Task::with_length(40), Task::with_length(60)]));
+        schedule.add_core(Core::from(vec![Task::with_length(70)]));
+        schedule
+    }
+
+    #[test]
+    fn test_svg() {
+        let schedule = schedule();
+        let svg = Gantt::new(&schedule).with_title("t.case").to_svg();
+
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("class=\"task\"").count(), 3);
+        assert_eq!(svg.matches("class=\"core critical\"").count(), 1);
+        assert!(svg.contains("t.case (makespan 100)"));
+        assert!(svg.contains("Task t2: core 1, position 2, length 60, from 40 to 100"));
+        // Tasks are scaled to makespan, so the longest row spans whole chart.
+        assert!(svg.contains(&format!("x=\"{:.1}\"", LABEL_WIDTH + 0.4 * (WIDTH - LABEL_WIDTH - MARGIN_RIGHT))));
+    }
+
+    #[test]
+    fn test_html() {
+        let schedule = schedule();
+        let html = Gantt::new(&schedule).with_title("<case>").render(GanttFormat::Html);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>&lt;case&gt;</title>"));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("Makespan 100 on 2 cores, 3 tasks."));
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(GanttFormat::from_path("out/schedule.HTML"), Some(GanttFormat::Html));
+        assert_eq!(GanttFormat::from_path("schedule.svg"), Some(GanttFormat::Svg));
+        assert_eq!(GanttFormat::from_path("schedule"), None);
+    }
+}
@@ -1,11 +1,44 @@
 // #![allow(dead_code)]
-use crate::utils::{Case, Core, Schedule};
+use crate::solver::{Budget, Observer, Outcome, Solver, SolverStats};
+use crate::utils::{Case, Core, Schedule, Settings};
+use std::time::Instant;
+
+/// Longest Tasks First greedy algorithm.
+pub struct Lpt;
+
+impl Solver for Lpt {
+    fn name(&self) -> &'static str {
+        "lpt"
+    }
+
+    fn solve(&self, case: &Case, _: Option<Schedule>, _: &Budget, observer: &mut dyn Observer) -> Outcome {
+        let timer = Instant::now();
+        let schedule = schedule(case);
+
+        let makespan = schedule.makespan().unwrap();
+        observer.record(0, makespan);
+        observer.finish();
+
+        Outcome {
+            schedule,
+            stats: SolverStats {
+                elapsed: timer.elapsed(),
+                optimal: makespan <= case.lower_bound(),
+                ..SolverStats::default()
+            },
+        }
+    }
+}
+
+pub fn solver(_: &Settings) -> Result<Box<dyn Solver>, String> {
+    Ok(Box::new(Lpt))
+}
 
 pub fn schedule(case: &Case) -> Schedule {
     let mut cpu: Vec<Core> = (0..case.cores()).map(|_| Core::new()).collect();
 
     let mut tasks = case.tasks().to_owned();
-    tasks.sort_unstable_by(|a, b| b.length().cmp(&a.length()));
+    tasks.sort_unstable_by_key(|task| std::cmp::Reverse(task.length()));
 
     tasks.iter().for_each(|&task| {
         cpu.iter_mut()
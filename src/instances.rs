@@ -0,0 +1,359 @@
+//! Formats of benchmark instances from literature, besides the native one.
+
+use crate::utils::{Case, Task};
+use std::{error::Error, fmt, path::Path, str::FromStr};
+
+/// Format of case file.
+///
+/// All formats consist of whitespace separated integers, `n` being number of tasks and `m` number of cores.
+/// Blank lines are skipped and `#` starts comment lasting till the end of line.
+/// - native: `m`, `n` and lengths of tasks, each in separate line
+/// - Franca/Frangioni/Gendreau uniform and non-uniform sets: `n m` in the first line, then lengths of tasks
+/// - Dell'Amico–Martello: `n`, `m` and lengths of tasks, each in separate line
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum CaseFormat {
+    #[default]
+    Native,
+    Frangioni,
+    DellAmicoMartello,
+}
+
+impl fmt::Display for CaseFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Native => write!(f, "native"),
+            Self::Frangioni => write!(f, "ffg"),
+            Self::DellAmicoMartello => write!(f, "dm"),
+        }
+    }
+}
+
+impl FromStr for CaseFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "native" => Ok(Self::Native),
+            "ffg" => Ok(Self::Frangioni),
+            "dm" => Ok(Self::DellAmicoMartello),
+            _ => Err(format!("Unknown case format `{}`.", name)),
+        }
+    }
+}
+
+/// Kind of problem found in case file.
+#[derive(PartialEq, Debug, Clone)]
+pub enum ParseErrorKind {
+    /// File holds no numbers at all.
+    Empty,
+    InvalidNumber(String),
+    /// Header doesn't have layout of the format.
+    InvalidHeader(CaseFormat),
+    /// Format can't be told from header.
+    UnknownFormat,
+    ZeroCores,
+    /// Number of tasks declared in header differs from number of lengths.
+    CountMismatch { declared: u64, found: usize },
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "case is empty"),
+            Self::InvalidNumber(value) => write!(f, "`{}` isn't a non-negative integer", value),
+            Self::InvalidHeader(CaseFormat::Frangioni) => {
+                write!(f, "header of ffg case has to hold number of tasks and cores in one line")
+            }
+            Self::InvalidHeader(format) => write!(
+                f,
+                "header of {} case has to hold number of {} in separate lines",
+                format,
+                match format {
+                    CaseFormat::DellAmicoMartello => "tasks and cores",
+                    _ => "cores and tasks",
+                }
+            ),
+            Self::UnknownFormat => write!(f, "format can't be detected from header"),
+            Self::ZeroCores => write!(f, "case has to have at least one core"),
+            Self::CountMismatch { declared, found } => {
+                write!(f, "{} tasks are declared, but {} are given", declared, found)
+            }
+        }
+    }
+}
+
+/// Problem found in case file, at 1-based line and column.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    fn at(token: &Token, kind: ParseErrorKind) -> Self {
+        Self {
+            line: token.line,
+            column: token.column,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Number read from case file, `None` when it's invalid.
+#[derive(PartialEq, Debug, Clone)]
+struct Token {
+    value: Option<u64>,
+    line: usize,
+    column: usize,
+}
+
+/// Splits `source` into tokens of every line which holds any, reporting invalid numbers.
+fn tokenize(source: &str) -> (Vec<Vec<Token>>, Vec<ParseError>) {
+    let mut lines = Vec::new();
+    let mut errors = Vec::new();
+    for (number, line) in source.lines().enumerate() {
+        let content = line.split('#').next().unwrap_or_default();
+        let mut tokens = Vec::new();
+        let mut start = None;
+        // Extra whitespace terminates the last token.
+        for (column, character) in content.chars().chain(Some(' ')).enumerate() {
+            match (character.is_whitespace(), start) {
+                (false, None) => start = Some(column),
+                (true, Some(first)) => {
+                    let text: String = content.chars().skip(first).take(column - first).collect();
+                    let token = Token {
+                        value: text.parse().ok(),
+                        line: number + 1,
+                        column: first + 1,
+                    };
+                    if token.value.is_none() {
+                        errors.push(ParseError::at(&token, ParseErrorKind::InvalidNumber(text)));
+                    }
+                    tokens.push(token);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if !tokens.is_empty() {
+            lines.push(tokens);
+        }
+    }
+    (lines, errors)
+}
+
+impl CaseFormat {
+    /// Guesses format from extension of `path`, `.ffg` or `.dm`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        path.as_ref().extension()?.to_str()?.to_lowercase().parse().ok()
+    }
+
+    /// Tells format of case from its header.
+    ///
+    /// Native and Dell'Amico–Martello headers look the same and case declaring wrong count
+    /// would fit the other one, so two-line headers are always taken for native ones.
+    /// Dell'Amico–Martello cases have to be requested, see [`CaseFormat::from_path`].
+    pub fn detect(source: &str) -> Result<Self, ParseError> {
+        let (lines, _) = tokenize(source);
+        Self::detect_lines(&lines)
+    }
+
+    fn detect_lines(lines: &[Vec<Token>]) -> Result<Self, ParseError> {
+        match lines {
+            [] => Err(empty()),
+            [header, ..] if header.len() == 2 => Ok(Self::Frangioni),
+            [first, second, ..] if first.len() == 1 && second.len() == 1 => Ok(Self::Native),
+            [first, ..] => Err(ParseError::at(&first[0], ParseErrorKind::UnknownFormat)),
+        }
+    }
+
+    /// Reads case written in this format, failing at its first problem.
+    pub fn parse(&self, source: &str) -> Result<Case, ParseError> {
+        let (lines, mut errors) = tokenize(source);
+        let case = self.check(&lines, &mut errors);
+        errors.sort_by_key(|error| (error.line, error.column));
+        match (case, errors.into_iter().next()) {
+            (_, Some(error)) => Err(error),
+            (Some(case), None) => Ok(case),
+            (None, None) => unreachable!("Case is built when no problem was found."),
+        }
+    }
+
+    /// Reads case, detecting its format (native or Franca/Frangioni/Gendreau).
+    pub fn read(source: &str) -> Result<Case, ParseError> {
+        Self::detect(source)?.parse(source)
+    }
+
+    /// Returns every problem of case in `format`, detected when `None`, ordered by position.
+    pub fn lint(source: &str, format: Option<Self>) -> Vec<ParseError> {
+        let (lines, mut errors) = tokenize(source);
+        match format.map_or_else(|| Self::detect_lines(&lines), Ok) {
+            Ok(format) => {
+                format.check(&lines, &mut errors);
+            }
+            Err(error) => errors.push(error),
+        }
+        errors.sort_by_key(|error| (error.line, error.column));
+        errors.dedup();
+        errors
+    }
+
+    /// Checks structure of case, returning it when neither this nor earlier check found problems.
+    fn check(&self, lines: &[Vec<Token>], errors: &mut Vec<ParseError>) -> Option<Case> {
+        let (tasks, cores, skipped) = match (self, lines) {
+            (_, []) => {
+                errors.push(empty());
+                return None;
+            }
+            (Self::Frangioni, [header, ..]) if header.len() == 2 => (&header[0], &header[1], 1),
+            (Self::Native, [cores, tasks, ..]) if cores.len() == 1 && tasks.len() == 1 => (&tasks[0], &cores[0], 2),
+            (Self::DellAmicoMartello, [tasks, cores, ..]) if cores.len() == 1 && tasks.len() == 1 => {
+                (&tasks[0], &cores[0], 2)
+            }
+            (_, [first, rest @ ..]) => {
+                // The offending line is the first one not having single number in two-line headers.
+                let line = match self {
+                    Self::Frangioni => first,
+                    _ if first.len() != 1 => first,
+                    _ => rest.first().unwrap_or(first),
+                };
+                errors.push(ParseError::at(&line[0], ParseErrorKind::InvalidHeader(*self)));
+                return None;
+            }
+        };
+        if cores.value == Some(0) {
+            errors.push(ParseError::at(cores, ParseErrorKind::ZeroCores));
+        }
+        let lengths: Vec<&Token> = lines.iter().skip(skipped).flatten().collect();
+        if let Some(declared) = tasks.value {
+            if declared != lengths.len() as u64 {
+                errors.push(ParseError::at(
+                    tasks,
+                    ParseErrorKind::CountMismatch {
+                        declared,
+                        found: lengths.len(),
+                    },
+                ));
+            }
+        }
+        if !errors.is_empty() {
+            return None;
+        }
+
+        let mut case = Case::new().with_cores(cores.value?);
+        case.add_tasks(lengths.iter().filter_map(|token| token.value).map(Task::with_length).collect());
+        Some(case)
+    }
+
+    /// Writes `case` in this format.
+    pub fn write(&self, case: &Case) -> String {
+        let lengths: Vec<String> = case.tasks().iter().map(|task| task.length().to_string()).collect();
+        let header = match self {
+            Self::Native => format!("{}\n{}", case.cores(), lengths.len()),
+            Self::Frangioni => format!("{} {}", lengths.len(), case.cores()),
+            Self::DellAmicoMartello => format!("{}\n{}", lengths.len(), case.cores()),
+        };
+        format!("{}\n{}\n", header, lengths.join("\n"))
+    }
+}
+
+fn empty() -> ParseError {
+    ParseError {
+        line: 1,
+        column: 1,
+        kind: ParseErrorKind::Empty,
+    }
+}
+
+#[cfg(test)]
+mod test_instances {
+    use super::*;
+
+    fn lengths(case: &Case) -> Vec<u64> {
+        let mut lengths: Vec<u64> = case.tasks().iter().map(Task::length).collect();
+        lengths.sort_unstable();
+        lengths
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(CaseFormat::detect("2\n3\n5\n6\n7\n"), Ok(CaseFormat::Native));
+        assert_eq!(CaseFormat::detect("3\n2\n5\n6\n7\n"), Ok(CaseFormat::Native));
+        assert_eq!(CaseFormat::detect("3 2\n5\n6\n7\n"), Ok(CaseFormat::Frangioni));
+        assert_eq!(CaseFormat::detect("3 2\n5 6 7\n"), Ok(CaseFormat::Frangioni));
+        assert_eq!(CaseFormat::detect("4\n4\n5\n6\n7\n"), Ok(CaseFormat::Native));
+        assert!(CaseFormat::detect("1 2 3\n5\n").is_err());
+        assert!(CaseFormat::detect("").is_err());
+        assert!(CaseFormat::detect("# comment only\n").is_err());
+    }
+
+    #[test]
+    fn test_formats_read_the_same_case() {
+        let native = CaseFormat::read("2\n3\n5\n6\n7\n").unwrap();
+        let frangioni = CaseFormat::read("3 2\n5\n6\n\n7\n").unwrap();
+        let martello = CaseFormat::DellAmicoMartello.parse("3\n2\n5\n6\n7").unwrap();
+
+        for case in &[&frangioni, &martello] {
+            assert_eq!(case.cores(), native.cores());
+            assert_eq!(lengths(case), vec![5, 6, 7]);
+        }
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(CaseFormat::from_path("instances/u_10_2.DM"), Some(CaseFormat::DellAmicoMartello));
+        assert_eq!(CaseFormat::from_path("instances/u_10_2.ffg"), Some(CaseFormat::Frangioni));
+        assert_eq!(CaseFormat::from_path("instances/test.case"), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CaseFormat::Frangioni.parse("3 2\n5\n6\n").is_err());
+        assert!(CaseFormat::Native.parse("3 2\n5\n6\n7\n").is_err());
+        assert!(CaseFormat::DellAmicoMartello.parse("1\n0\n5\n").is_err());
+    }
+
+    #[test]
+    fn test_write_reads_back() {
+        let case = CaseFormat::read("2\n3\n5\n6\n7\n").unwrap();
+        for format in &[CaseFormat::Native, CaseFormat::Frangioni, CaseFormat::DellAmicoMartello] {
+            let written = format.write(&case);
+            let read = format.parse(&written).unwrap();
+            assert_eq!(read.cores(), 2);
+            assert_eq!(lengths(&read), vec![5, 6, 7]);
+        }
+    }
+
+    #[test]
+    fn test_lint_reports_every_problem() {
+        let problems = CaseFormat::lint("0\n4 # tasks\n5\nx\n7 -1\n", None);
+        let found: Vec<(usize, usize)> = problems.iter().map(|problem| (problem.line, problem.column)).collect();
+
+        assert_eq!(found, vec![(1, 1), (4, 1), (5, 3)]);
+        assert_eq!(problems[0].kind, ParseErrorKind::ZeroCores);
+        assert_eq!(problems[1].kind, ParseErrorKind::InvalidNumber("x".to_string()));
+
+        let problems = CaseFormat::lint("2\n3\n5\n", Some(CaseFormat::Native));
+        assert_eq!(problems[0].kind, ParseErrorKind::CountMismatch { declared: 3, found: 1 });
+        assert!(CaseFormat::lint("2\n1\n5\n", None).is_empty());
+
+        // Wrong count isn't read as Dell'Amico–Martello case with swapped header.
+        let problems = CaseFormat::lint("3\n4\n5\n6\n7\n", None);
+        assert_eq!(problems.len(), 1);
+        assert_eq!((problems[0].line, problems[0].column), (2, 1));
+        assert_eq!(problems[0].kind, ParseErrorKind::CountMismatch { declared: 4, found: 3 });
+        assert!(CaseFormat::lint("3\n4\n5\n6\n7\n", Some(CaseFormat::DellAmicoMartello)).is_empty());
+
+        let problems = CaseFormat::lint("2 1\n5\n", Some(CaseFormat::Native));
+        assert_eq!(problems[0].kind, ParseErrorKind::InvalidHeader(CaseFormat::Native));
+    }
+}
@@ -1,41 +1,29 @@
-use crate::utils::{Case, Task};
+use crate::instances::{CaseFormat, ParseError};
+use crate::utils::{Case, Schedule};
 use std::io::Write;
-use std::num::ParseIntError;
 use std::path::Path;
 use std::str::FromStr;
-use std::{error::Error, fs};
+use std::{error::Error, fmt, fs};
 
-impl ToString for Case {
-    fn to_string(&self) -> String {
-        return self
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let serialized = self
             .tasks()
             .iter()
             .fold(
                 format!("{}\n{}\n", self.cores(), self.tasks().len()),
                 |val, cur| format!("{}{}\n", val, cur.length()),
-            )
-            .trim_end()
-            .to_owned();
+            );
+        write!(f, "{}", serialized.trim_end())
     }
 }
 
+/// Reads case in native format, see [`CaseFormat`].
 impl FromStr for Case {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(serialized: &str) -> Result<Self, Self::Err> {
-        let lines: Vec<&str> = serialized.lines().collect();
-
-        let cores = lines[0].parse::<u64>()?;
-        let mut tasks: Vec<Task> = Vec::new();
-        for line in lines.iter().skip(2) {
-            let length = line.parse::<u64>()?;
-            tasks.push(Task::with_length(length));
-        }
-
-        let mut case = Case::new().with_cores(cores);
-        case.add_tasks(tasks);
-
-        Ok(case)
+        CaseFormat::Native.parse(serialized)
     }
 }
 
@@ -44,7 +32,11 @@ impl Case {
         let full_path = path.into();
         let path = Path::new(&full_path);
         fs::create_dir_all(path.parent().unwrap_or(Path::new("./")))?;
-        let mut file = fs::OpenOptions::new().write(true).create(true).open(path)?;
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
 
         let case = self.to_string();
         file.write_all(case.as_bytes())?;
@@ -52,22 +44,111 @@ impl Case {
         Ok(())
     }
 
+    /// Reads case in format told by extension of `path`, native one by default.
     pub fn read_from_file<P: Into<String>>(path: P) -> Result<Case, Box<dyn Error>> {
-        let serialized = fs::read_to_string(path.into())?.trim().to_owned();
-        let case = Case::from_str(&serialized)?;
+        Self::read_in_format(path, None)
+    }
 
-        Ok(case)
+    /// Reads case in `format`, falling back to extension of `path` when `None`, see [`CaseFormat`].
+    pub fn read_in_format<P: Into<String>>(path: P, format: Option<CaseFormat>) -> Result<Case, Box<dyn Error>> {
+        let path = path.into();
+        let format = format.or_else(|| CaseFormat::from_path(&path)).unwrap_or_default();
+        let serialized = fs::read_to_string(&path)?;
+        Ok(format.parse(&serialized)?)
     }
 
     pub fn serialize(&self) -> String {
         self.to_string()
     }
+
+    /// Returns case serialized with `seed` it was generated with in leading `#` comment,
+    /// so it can be generated again.
+    pub fn serialize_with_seed(&self, seed: u64) -> String {
+        format!("# seed {}\n{}", seed, self)
+    }
+}
+
+/// Format in which schedules are written.\
+/// Json: the same as generator's `.schedule` files, can be read back as initial solution\
+/// Text: every core in separate line, as lengths of its tasks separated by spaces
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum ScheduleFormat {
+    #[default]
+    Json,
+    Text,
+}
+
+impl fmt::Display for ScheduleFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Text => write!(f, "text"),
+        }
+    }
+}
+
+impl FromStr for ScheduleFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "json" => Ok(Self::Json),
+            "text" => Ok(Self::Text),
+            _ => Err(format!("Unknown schedule format `{}`.", name)),
+        }
+    }
+}
+
+impl Schedule {
+    /// Reads schedule serialized as JSON, e.g. one written by case generator.
+    pub fn read_from_file<P: Into<String>>(path: P) -> Result<Schedule, Box<dyn Error>> {
+        let serialized = fs::read_to_string(path.into())?;
+        Ok(serde_json::from_str(&serialized)?)
+    }
+
+    /// Returns schedule written in `format`.
+    pub fn to_format(&self, format: ScheduleFormat) -> String {
+        match format {
+            ScheduleFormat::Json => self.serialize(),
+            ScheduleFormat::Text => self
+                .cores()
+                .iter()
+                .map(|core| {
+                    let lengths: Vec<String> = core.get_tasks().iter().map(|task| task.length().to_string()).collect();
+                    lengths.join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    /// Returns schedule written in `format` together with `seed` of run which made it,
+    /// as `seed` field of JSON (ignored when schedule is read back) or as leading `#` comment of text.
+    pub fn to_format_with_seed(&self, format: ScheduleFormat, seed: u64) -> String {
+        match format {
+            ScheduleFormat::Json => {
+                let mut value = serde_json::to_value(self).unwrap();
+                value["seed"] = seed.into();
+                value.to_string()
+            }
+            ScheduleFormat::Text => format!("# seed {}\n{}", seed, self.to_format(format)),
+        }
+    }
+
+    /// Writes schedule in `format`, noting `seed` of run which made it, see [`Schedule::to_format_with_seed`].
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P, format: ScheduleFormat, seed: u64) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        fs::create_dir_all(path.parent().unwrap_or(Path::new("./")))?;
+        fs::write(path, self.to_format_with_seed(format, seed))?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod test_case_io {
     use super::*;
-    use crate::utils::Task;
+    use crate::instances::ParseErrorKind;
+    use crate::utils::{Core, Task};
 
     #[test]
     fn test_case_to_string() {
@@ -82,7 +163,7 @@ mod test_case_io {
     #[test]
     fn test_case_from_str() {
         let serialized = "2\n5\n4\n6\n9\n1\n3";
-        let case = Case::from_str(serialized).unwrap_or(Case::new());
+        let case = Case::from_str(serialized).unwrap_or_default();
 
         let task_lengths: Vec<u64> = case.tasks().iter().map(|task| task.length()).collect();
 
@@ -90,4 +171,83 @@ mod test_case_io {
         assert_eq!(case.tasks().len(), 5);
         assert_eq!(task_lengths, vec![4, 6, 9, 1, 3]);
     }
+
+    #[test]
+    fn test_case_from_str_with_comments() {
+        let case: Case = "# two cores\n2\n\n  3   # tasks\n4\n6 # longest\n1\n".parse().unwrap();
+
+        assert_eq!(case.cores(), 2);
+        assert_eq!(case.tasks().len(), 3);
+    }
+
+    #[test]
+    fn test_case_from_str_errors() {
+        let error = Case::from_str("").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::Empty);
+
+        let error = Case::from_str("0\n1\n5\n").unwrap_err();
+        assert_eq!((error.line, error.column, error.kind), (1, 1, ParseErrorKind::ZeroCores));
+
+        let error = Case::from_str("2\n3\n5\n6\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.kind, ParseErrorKind::CountMismatch { declared: 3, found: 2 });
+
+        let error = Case::from_str("2\n2\n5\n  6x\n").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 3));
+        assert_eq!(error.to_string(), "line 4, column 3: `6x` isn't a non-negative integer");
+    }
+
+    #[test]
+    fn test_case_read_in_format() {
+        let dir = std::env::temp_dir();
+        let native = dir.join(format!("max-read-{}.case", std::process::id()));
+        let martello = dir.join(format!("max-read-{}.dm", std::process::id()));
+        fs::write(&native, "3\n4\n5\n6\n7\n").unwrap();
+        fs::write(&martello, "3\n4\n5\n6\n7\n").unwrap();
+        let native = native.to_string_lossy().into_owned();
+        let martello = martello.to_string_lossy().into_owned();
+
+        // Wrong count of native case isn't taken for other format.
+        assert!(Case::read_from_file(native.as_str()).is_err());
+        assert_eq!(Case::read_from_file(martello.as_str()).unwrap().cores(), 4);
+        let case = Case::read_in_format(native.as_str(), Some(CaseFormat::DellAmicoMartello)).unwrap();
+        assert_eq!((case.cores(), case.tasks().len()), (4, 3));
+
+        fs::remove_file(native).unwrap();
+        fs::remove_file(martello).unwrap();
+    }
+
+    #[test]
+    fn test_schedule_formats() {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from(vec![Task::with_length(3), Task::with_length(6)]));
+        schedule.add_core(Core::new());
+        schedule.add_core(Core::from(vec![Task::with_length(10)]));
+
+        assert_eq!(schedule.to_format(ScheduleFormat::Text), "3 6\n\n10");
+        assert_eq!(schedule.to_format(ScheduleFormat::Json), schedule.serialize());
+        assert_eq!("text".parse(), Ok(ScheduleFormat::Text));
+        assert!("csv".parse::<ScheduleFormat>().is_err());
+    }
+
+    #[test]
+    fn test_seed_is_written_and_ignored_on_read() {
+        let mut case = Case::new().with_cores(2);
+        case.add_tasks(vec![Task::with_length(4), Task::with_length(7)]);
+        let serialized = case.serialize_with_seed(42);
+        assert!(serialized.starts_with("# seed 42\n"));
+        assert_eq!(serialized.parse::<Case>().unwrap(), case);
+
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from(vec![Task::with_length(4)]));
+        schedule.add_core(Core::from(vec![Task::with_length(7)]));
+        assert_eq!(schedule.to_format_with_seed(ScheduleFormat::Text, 42), "# seed 42\n4\n7");
+
+        let path = std::env::temp_dir().join(format!("max-seed-{}.schedule", std::process::id()));
+        schedule.save_to_file(&path, ScheduleFormat::Json, 42).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["seed"], 42);
+        assert_eq!(Schedule::read_from_file(path.to_string_lossy()).unwrap(), schedule);
+        fs::remove_file(path).unwrap();
+    }
 }
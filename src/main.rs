@@ -1,91 +1,224 @@
 #![allow(dead_code)]
 mod app;
+mod config;
+mod gantt;
 mod greedy;
 
 mod random;
 
+mod instances;
 mod io;
 mod modules;
+mod multistart;
+mod observers;
+mod portfolio;
+mod plot;
 mod pretty_print;
 mod serializer;
 mod simulated_annealing;
+mod solver;
+mod tune;
 mod utils;
 
 use std::{
+    env,
     error::Error,
+    fmt,
     fs::{self, File, OpenOptions},
     path::Path,
+    str::FromStr,
+    time::Duration,
 };
 
 use app::App;
+use gantt::{Gantt, GanttFormat};
+use instances::CaseFormat;
+use io::ScheduleFormat;
+use config::{AnnealingConfig, Config, ENV_CONFIG};
 use clap::load_yaml;
 use std::io::Write;
 use utils::Settings;
 
 use modules::generator;
+use plot::{Plot, Run};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use tune::{SearchSpace, Tuner};
+use utils::{Case, Schedule};
 
 fn open_file(name: &str, dir: &str) -> Result<File, Box<dyn Error>> {
     fs::create_dir_all(dir)?;
     let file = OpenOptions::new()
         .create(true)
-        .write(true)
         .append(true)
         .open(format!("{}/{}", dir, name))?;
 
     Ok(file)
 }
 
+/// Parses given seed, or draws new one when none was given.
+fn seed(value: Option<&str>) -> u64 {
+    value.map_or_else(rand::random, |seed| {
+        seed.parse().unwrap_or_else(|_| {
+            eprintln!("Invalid seed {}, it has to be a non-negative integer.", seed);
+            std::process::exit(1);
+        })
+    })
+}
+
+/// Returns value of argument `name` if it was given on command line, ignoring its default value.
+fn given<'a>(args: &'a clap::ArgMatches, name: &str) -> Option<&'a str> {
+    args.value_of(name).filter(|_| args.occurrences_of(name) > 0)
+}
+
+/// Parses value of argument `name` given on command line, exiting with message when it's invalid.
+fn parsed<T: FromStr>(args: &clap::ArgMatches, name: &str) -> Option<T>
+where
+    T::Err: fmt::Display,
+{
+    given(args, name).map(|value| {
+        value.parse().unwrap_or_else(|err| {
+            eprintln!("Invalid value {} of --{}. {}", value, name, err);
+            std::process::exit(1);
+        })
+    })
+}
+
+/// Parses values of list argument `name` given on command line, exiting with message when any is invalid.
+fn parsed_values<T: FromStr>(args: &clap::ArgMatches, name: &str) -> Option<Vec<T>>
+where
+    T::Err: fmt::Display,
+{
+    args.values_of(name).filter(|_| args.occurrences_of(name) > 0).map(|values| {
+        values
+            .map(|value| {
+                value.parse().unwrap_or_else(|err| {
+                    eprintln!("Invalid value {} of --{}. {}", value, name, err);
+                    std::process::exit(1);
+                })
+            })
+            .collect()
+    })
+}
+
+/// Converts configured number of `seconds` to duration, exiting when it's negative or not finite.
+fn duration(seconds: f64, name: &str) -> Duration {
+    Duration::try_from_secs_f64(seconds).unwrap_or_else(|err| {
+        eprintln!("Invalid {} of {} seconds. {}.", name, seconds, err);
+        std::process::exit(1);
+    })
+}
+
 fn main() {
     let cli_settings = load_yaml!("settings.yaml");
     let app_args = clap::App::from_yaml(cli_settings).get_matches();
 
     match app_args.subcommand() {
-        ("generate", Some(args)) => {
-            let path = Path::new(args.value_of("output").unwrap());
-            let filename = path.file_name().unwrap().to_str().unwrap();
-            let dir = path.parent().unwrap().to_str().unwrap();
+        ("solve", Some(args)) => solve(args),
+        ("config", Some(args)) => match args.subcommand() {
+            ("show", Some(args)) => show_config(args),
+            _ => unreachable!("Subcommand is required."),
+        },
+        ("plot", Some(args)) => {
+            if let Err(err) = plot(args) {
+                eprintln!("Plotting failed. {}", err);
+                std::process::exit(1);
+            }
+        }
+        ("gantt", Some(args)) => {
+            if let Err(err) = gantt(args) {
+                eprintln!("Drawing Gantt chart failed. {}", err);
+                std::process::exit(1);
+            }
+        }
+        ("convert", Some(args)) => {
+            if let Err(err) = convert(args) {
+                eprintln!("Conversion failed. {}", err);
+                std::process::exit(1);
+            }
+        }
+        ("lint", Some(args)) => {
+            if !lint(args) {
+                std::process::exit(1);
+            }
+        }
+        ("generate", Some(args)) => generate_case(args),
+        ("tune", Some(args)) => {
+            if let Err(err) = run_tuning(args) {
+                eprintln!("Tuning failed. {}", err);
+                std::process::exit(1);
+            }
+        }
+        _ => unreachable!("Subcommand is required."),
+    }
+}
 
-            let mut case_output = open_file(filename, dir).unwrap();
-            let mut schedule_output = open_file(&format!("{}.schedule", filename), dir).unwrap();
+fn generate_case(args: &clap::ArgMatches) {
+    let path = Path::new(args.value_of("output").unwrap());
+    let filename = path.file_name().unwrap().to_str().unwrap();
+    let dir = path.parent().unwrap().to_str().unwrap();
 
-            let cores = args.value_of("cores").unwrap().parse::<u16>().unwrap();
-            let optimal = args
-                .value_of("optimal")
-                .and_then(|val| val.parse::<u64>().ok());
+    let mut case_output = open_file(filename, dir).unwrap();
+    let mut schedule_output = open_file(&format!("{}.schedule", filename), dir).unwrap();
 
-            let (case, schedule) = generator::generate(cores, optimal);
+    let cores = args.value_of("cores").unwrap().parse::<u16>().unwrap();
+    let optimal = args
+        .value_of("optimal")
+        .and_then(|val| val.parse::<u64>().ok());
 
-            let case_serialized = case.serialize();
-            let schedule_serialized = schedule.serialize();
+    let seed = seed(args.value_of("seed"));
+    println!("Seed: {}", seed);
 
-            case_output.write_all(case_serialized.as_bytes()).unwrap();
-            schedule_output
-                .write_all(schedule_serialized.as_bytes())
-                .unwrap();
-        }
-        _ => (),
-    }
+    let (case, schedule) = generator::generate(cores, optimal, &mut ChaCha12Rng::seed_from_u64(seed));
+
+    let case_serialized = case.serialize_with_seed(seed);
+    let schedule_serialized = schedule.to_format_with_seed(ScheduleFormat::Json, seed);
+
+    case_output.write_all(case_serialized.as_bytes()).unwrap();
+    schedule_output
+        .write_all(schedule_serialized.as_bytes())
+        .unwrap();
+}
+
+fn solve(app_args: &clap::ArgMatches) {
+    let config = layered_config(app_args)
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        })
+        .merge(cli_config(app_args));
 
     let settings = Settings {
         prompt: app_args.is_present("prompt"),
-        unbuffered: app_args.is_present("unbuffered"),
-        verbosity: app_args.occurrences_of("verbose") as u8,
+        unbuffered: config.unbuffered.unwrap(),
+        verbosity: config.verbosity.unwrap(),
+        task_labels: parsed(app_args, "task-labels").unwrap_or_default(),
         input_files: app_args
             .values_of("files")
-            .and_then(|vals| Some(vals.map(ToString::to_string).collect::<Vec<String>>()))
-            .unwrap_or(Vec::new()),
-        log_file: app_args.value_of("logfile").unwrap().to_string(),
-        kill_time: app_args.value_of("kill").unwrap().parse().unwrap(),
+            .map(|vals| vals.map(ToString::to_string).collect::<Vec<String>>())
+            .unwrap_or_default(),
+        case_format: parsed(app_args, "case-format"),
+        log_file: config.log_file.unwrap(),
+        log_format: config.log_format.unwrap(),
+        output: app_args.value_of("output").map(ToString::to_string),
+        format: config.format.unwrap(),
+        kill_time: config.time_limit.unwrap(),
+        seed: config.seed.unwrap_or_else(rand::random),
+        cpu_time: config.cpu_time.map(|seconds| duration(seconds, "CPU time limit")),
+        max_iterations: config.max_iterations,
+        max_evaluations: config.max_evaluations,
+        initial: config.initial,
+        checkpoint: config.checkpoint,
+        checkpoint_interval: duration(config.checkpoint_every.unwrap(), "checkpoint interval"),
+        resume: config.resume,
+        algorithm: config.algorithm.unwrap(),
+        portfolio: config.portfolio.unwrap_or_default(),
+        runs: config.runs.unwrap(),
+        starts: config.starts.unwrap_or_default(),
+        annealing: config.annealing,
     };
 
-    Settings::init(
-        settings.prompt,
-        settings.unbuffered,
-        settings.verbosity,
-        settings.input_files,
-        settings.log_file,
-        settings.kill_time,
-    );
+    Settings::init(settings);
 
     let settings = Settings::get().unwrap().read().unwrap();
 
@@ -101,26 +234,217 @@ fn main() {
     for file in &settings.input_files {
         println!("Processing {}", file);
         match App::process(file) {
-            Ok(schedule) => println!("SA solution: {}", schedule.makespan().unwrap()),
+            Ok(schedule) => println!("Solution: {}", schedule.makespan().unwrap()),
             Err(err) => eprintln!("An error occured during processing. {}", err),
         }
     }
 
     if settings.prompt {
         loop {
-            let file: String;
-
             println!("Enter path to data file (or type `exit` to end program): ");
-            file = readln!();
+            let file: String = readln!();
             if file.eq_ignore_ascii_case("exit") {
                 break;
             }
 
             println!("Processing {}", file);
             match App::process(&file) {
-                Ok(schedule) => println!("SA solution: {}", schedule.makespan().unwrap()),
+                Ok(schedule) => println!("Solution: {}", schedule.makespan().unwrap()),
                 Err(err) => eprintln!("An error occured during processing. {}", err),
             }
         }
     }
 }
+
+/// Merges built-in defaults, configuration file (`--config` or `PCMAX_CONFIG`) and `PCMAX_*` environment variables.
+fn layered_config(args: &clap::ArgMatches) -> Result<Config, String> {
+    let mut config = Config::defaults();
+    if let Some(path) = args
+        .value_of("config")
+        .map(ToString::to_string)
+        .or_else(|| env::var(ENV_CONFIG).ok())
+    {
+        let file = Config::read_from_file(path.as_str())
+            .map_err(|err| format!("Invalid configuration file {}. {}", path, err))?;
+        config = config.merge(file);
+    }
+    let environment = Config::from_env(env::vars()).map_err(|err| format!("Invalid environment. {}", err))?;
+    Ok(config.merge(environment))
+}
+
+/// Returns settings given as command line flags, the top layer of configuration.
+fn cli_config(args: &clap::ArgMatches) -> Config {
+    Config {
+        log_file: given(args, "logfile").map(ToString::to_string),
+        log_format: parsed(args, "log-format"),
+        unbuffered: Some(true).filter(|_| args.is_present("unbuffered")),
+        verbosity: Some(args.occurrences_of("verbose") as u8).filter(|&verbosity| verbosity > 0),
+        time_limit: parsed(args, "kill"),
+        cpu_time: parsed(args, "cpu-time"),
+        max_iterations: parsed(args, "max-iterations"),
+        max_evaluations: parsed(args, "max-evaluations"),
+        algorithm: given(args, "algorithm").map(ToString::to_string),
+        portfolio: args
+            .values_of("portfolio")
+            .map(|vals| vals.map(ToString::to_string).collect()),
+        runs: parsed(args, "runs"),
+        starts: parsed_values(args, "starts"),
+        seed: parsed(args, "seed"),
+        initial: given(args, "initial").map(ToString::to_string),
+        checkpoint: given(args, "checkpoint").map(ToString::to_string),
+        checkpoint_every: parsed(args, "checkpoint-every"),
+        resume: given(args, "resume").map(ToString::to_string),
+        format: parsed(args, "format"),
+        annealing: AnnealingConfig {
+            initial_temperature: parsed(args, "initial-temperature"),
+            final_temperature: parsed(args, "final-temperature"),
+            cooling: parsed(args, "cooling"),
+            operators: parsed(args, "operators"),
+            acceptance: parsed(args, "acceptance"),
+            iterations_per_temperature: parsed(args, "iterations-per-temperature"),
+            max_changeless_iterations: parsed(args, "max-changeless-iterations"),
+            calibration: parsed(args, "calibrate"),
+            restart: parsed(args, "restart"),
+            restart_after: parsed(args, "restart-after"),
+            max_restarts: parsed(args, "max-restarts"),
+        },
+    }
+}
+
+fn show_config(args: &clap::ArgMatches) {
+    match layered_config(args) {
+        Ok(config) => print!("{}", config),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn plot(args: &clap::ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut plot = Plot::new(args.value_of("axis").unwrap().parse()?);
+    if let Some(bound) = args.value_of("lower-bound") {
+        plot = plot.with_lower_bound(bound.parse()?);
+    }
+    if let Some(path) = args.value_of("case") {
+        plot = plot.with_lower_bound(Case::read_from_file(path)?.lower_bound());
+    }
+    let output = Path::new(args.value_of("output").unwrap());
+    fs::create_dir_all(output)?;
+
+    let mut all = Vec::new();
+    for log in args.values_of("logs").unwrap() {
+        let runs = Run::read_from_file(log).map_err(|err| format!("Can't read log {}. {}", log, err))?;
+        if runs.is_empty() {
+            println!("No runs logged in {}", log);
+        }
+        if args.is_present("overlay") {
+            all.extend(runs);
+            continue;
+        }
+        let name = Path::new(log).file_name().unwrap_or_default().to_string_lossy();
+        for (idx, run) in runs.iter().enumerate() {
+            let path = output.join(format!("{}.{}.svg", name, idx + 1));
+            fs::write(&path, plot.run(run)?)?;
+            println!("{} plotted to {}", run.name, path.display());
+        }
+    }
+    if args.is_present("overlay") && !all.is_empty() {
+        let path = output.join("overlay.svg");
+        fs::write(&path, plot.overlay(&all)?)?;
+        println!("{} runs plotted to {}", all.len(), path.display());
+    }
+
+    Ok(())
+}
+
+fn gantt(args: &clap::ArgMatches) -> Result<(), Box<dyn Error>> {
+    let path = args.value_of("schedule").unwrap();
+    let schedule = Schedule::read_from_file(path)?;
+    let output = args.value_of("output").unwrap();
+    let format = match args.value_of("format") {
+        Some(format) => format.parse()?,
+        None => GanttFormat::from_path(output).unwrap_or_default(),
+    };
+    let title = args.value_of("title").map_or_else(
+        || Path::new(path).file_name().unwrap_or_default().to_string_lossy().into_owned(),
+        ToString::to_string,
+    );
+
+    fs::write(output, Gantt::new(&schedule).with_title(title).render(format))?;
+    println!("Gantt chart of {} written to {}", path, output);
+
+    Ok(())
+}
+
+fn convert(args: &clap::ArgMatches) -> Result<(), Box<dyn Error>> {
+    let input = args.value_of("input").unwrap();
+    let source = fs::read_to_string(input)?;
+    let from = match args.value_of("from") {
+        Some(format) => format.parse()?,
+        None => match CaseFormat::from_path(input) {
+            Some(format) => format,
+            None => CaseFormat::detect(&source)?,
+        },
+    };
+    let to: CaseFormat = args.value_of("to").unwrap().parse()?;
+    let case = from.parse(&source)?;
+
+    let output = args.value_of("output").unwrap();
+    fs::write(output, to.write(&case))?;
+    println!("{} ({}) written to {} ({})", input, from, output, to);
+
+    Ok(())
+}
+
+/// Prints problems of every case file, returns `true` when there were none.
+fn lint(args: &clap::ArgMatches) -> bool {
+    let format = args.value_of("format").map(|format| format.parse().unwrap());
+    let mut problems = 0;
+    for file in args.values_of("files").unwrap() {
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("{}: {}", file, err);
+                problems += 1;
+                continue;
+            }
+        };
+        for problem in CaseFormat::lint(&source, format.or_else(|| CaseFormat::from_path(file))) {
+            println!("{}:{}:{}: {}", file, problem.line, problem.column, problem.kind);
+            problems += 1;
+        }
+    }
+    match problems {
+        0 => println!("No problems found."),
+        1 => println!("1 problem found."),
+        count => println!("{} problems found.", count),
+    }
+    problems == 0
+}
+
+fn run_tuning(args: &clap::ArgMatches) -> Result<(), Box<dyn Error>> {
+    let format = parsed(args, "case-format");
+    let instances = args
+        .values_of("instances")
+        .unwrap()
+        .map(|path| Case::read_in_format(path, format))
+        .collect::<Result<Vec<_>, _>>()?;
+    let space = match args.value_of("space") {
+        Some(path) => SearchSpace::read_from_file(path)?,
+        None => SearchSpace::standard(),
+    };
+    let output = args.value_of("output").unwrap();
+
+    let best = Tuner::new(instances, space)
+        .with_candidates(args.value_of("candidates").unwrap().parse()?)
+        .with_seeds(args.value_of("seeds").unwrap().parse()?)
+        .with_seed(args.value_of("seed").unwrap().parse()?)
+        .with_evaluations(args.value_of("evaluations").unwrap().parse()?)
+        .run(|round| println!("{}", round));
+
+    fs::write(output, best.to_string())?;
+    println!("Best configuration written to {}:\n{}", output, best);
+
+    Ok(())
+}
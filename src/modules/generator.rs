@@ -1,23 +1,21 @@
 use crate::utils::Core;
 use crate::utils::{Case, Schedule, Task};
 
-use rand::thread_rng as random_generator;
 use rand::Rng;
 
 /// Generates tuple (case, schedule)
 /// where `case` is test case with `cores` number of cores and random number of tasks
 /// which can be scheduled with optimal solution equal to `optimal`.
 /// `schedule` is this test case scheduled in optimal way.
+/// The same `random` generator state always produces the same case.
 ///
 /// # Panics
 /// When cores == 0
-pub fn generate(cores: u16, optimal: Option<u64>) -> (Case, Schedule) {
+pub fn generate<R: Rng>(cores: u16, optimal: Option<u64>, random: &mut R) -> (Case, Schedule) {
     if cores == 0 {
         panic!("Core count have to be positive.")
     }
 
-    let mut random = random_generator();
-
     // If optimal value was given then use it,
     // otherwise randomly choose optimal solution length.
     let optimal = optimal.unwrap_or(random.gen_range(100..=10000));
@@ -54,12 +52,21 @@ pub fn generate(cores: u16, optimal: Option<u64>) -> (Case, Schedule) {
 #[cfg(test)]
 mod test_generator {
     use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_generate_random_case() {
         // Generates case with 5 cores and optimal solution 3400.
-        let (_, schedule) = generate(5, Some(3400));
+        let (_, schedule) = generate(5, Some(3400), &mut StdRng::seed_from_u64(0));
 
         assert_eq!(schedule.makespan().unwrap(), 3400);
     }
+
+    #[test]
+    fn test_same_seed_same_case() {
+        let (first, _) = generate(4, None, &mut StdRng::seed_from_u64(42));
+        let (second, _) = generate(4, None, &mut StdRng::seed_from_u64(42));
+
+        assert_eq!(first, second);
+    }
 }
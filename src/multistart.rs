@@ -0,0 +1,514 @@
+use crate::simulated_annealing::{read_checkpoint, Checkpoint, Checkpoints, SimulatedAnnealingParams, Solution};
+use crate::solver::{Budget, Interests, Log, Observer, Outcome, Solver, SolverStats, Termination, RUN_LOG_CAPACITY};
+use crate::utils::{Case, Incumbent, Schedule, Settings};
+use crate::{greedy, random};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::{fmt, path::{Path, PathBuf}, str::FromStr, thread, time::Duration};
+
+/// Schedule from which single annealing run starts.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum InitialSolution {
+    /// Every task assigned to random core.
+    Random,
+    /// Longest Tasks First greedy schedule.
+    Lpt,
+    /// LPT schedule with some tasks moved to random cores.
+    PerturbedLpt,
+}
+
+impl InitialSolution {
+    /// Builds initial schedule of this kind for `case`.
+    pub fn build<R: Rng>(&self, case: &Case, rng: &mut R) -> Schedule {
+        match self {
+            Self::Random => random::schedule(case, rng),
+            Self::Lpt => greedy::schedule(case),
+            Self::PerturbedLpt => {
+                // Moving every tenth task is enough to leave LPT's basin
+                // while keeping most of its structure.
+                let moves = (case.tasks().len() / 10).max(1);
+                random::perturb(&greedy::schedule(case), moves, rng)
+            }
+        }
+    }
+}
+
+impl fmt::Display for InitialSolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Random => "random",
+            Self::Lpt => "lpt",
+            Self::PerturbedLpt => "perturbed-lpt",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for InitialSolution {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "random" => Ok(Self::Random),
+            "lpt" => Ok(Self::Lpt),
+            "perturbed-lpt" => Ok(Self::PerturbedLpt),
+            _ => Err(format!("Unknown initial solution `{}`.", name)),
+        }
+    }
+}
+
+/// Statistics of single annealing run.
+#[derive(Debug, Clone)]
+pub struct RunStats {
+    pub run: usize,
+    pub seed: u64,
+    /// Kind of initial solution, `None` when run started from given schedule.
+    pub initial: Option<InitialSolution>,
+    pub initial_makespan: u128,
+    pub best_makespan: u128,
+    pub iterations: u64,
+    pub elapsed: Duration,
+    pub termination: Option<Termination>,
+}
+
+impl fmt::Display for RunStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Run {} (seed {}, {}): {} -> {} in {} iterations, {:.2}s",
+            self.run,
+            self.seed,
+            self.initial.map_or_else(|| "given".to_string(), |initial| initial.to_string()),
+            self.initial_makespan,
+            self.best_makespan,
+            self.iterations,
+            self.elapsed.as_secs_f64()
+        )?;
+        if let Some(termination) = self.termination {
+            write!(f, ", stopped by {}", termination)?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of multi-start annealing.
+pub struct MultiStartResult {
+    /// Best schedule found by any run.
+    pub best: Schedule,
+    /// Index of run which found `best`.
+    pub winner: usize,
+    /// Statistics of every run, ordered by run index.
+    pub runs: Vec<RunStats>,
+    /// Log of the winning run.
+    pub log: Log,
+}
+
+/// Runs several independent Simulated Annealing simulations in parallel threads.
+///
+/// Every run gets its own seed (`seed + run index`) and initial solution
+/// (`starts` are assigned to runs in round-robin fashion).
+/// Runs share best found makespan and all of them stop once it reaches case's lower bound.
+pub struct MultiStart {
+    case: Case,
+    params: SimulatedAnnealingParams,
+    runs: usize,
+    seed: u64,
+    starts: Vec<InitialSolution>,
+    budget: Budget,
+    incumbent: Option<Incumbent>,
+    checkpoints: Option<Checkpoints>,
+    /// Checkpoints from which runs continue, indexed by run.
+    resume: Vec<Checkpoint>,
+    /// Schedule from which all runs start instead of built ones.
+    initial: Option<Schedule>,
+    /// Events kept by logs of runs.
+    interests: Interests,
+}
+
+impl MultiStart {
+    /// Creates multi-start simulation of `case` with single run starting from random schedule.
+    /// `initial_solution` and `seed` of `params` are overridden for every run.
+    pub fn new(case: Case, params: SimulatedAnnealingParams) -> Self {
+        let seed = params.seed;
+        let budget = Budget::with_time(Duration::from_secs(params.max_simulation_time.into()));
+        Self {
+            case,
+            params,
+            runs: 1,
+            seed,
+            starts: vec![InitialSolution::Random],
+            budget,
+            incumbent: None,
+            checkpoints: None,
+            resume: Vec::new(),
+            initial: None,
+            interests: Interests::default(),
+        }
+    }
+
+    /// Limits every run by `budget` instead of `max_simulation_time`,
+    /// sharing best makespan through budget's incumbent if it has one.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        if let Some(incumbent) = budget.incumbent() {
+            self.incumbent = Some(incumbent.clone());
+        }
+        self.budget = budget;
+        self
+    }
+
+    /// Makes runs share best makespan with other solvers through `incumbent`.
+    pub fn with_incumbent(mut self, incumbent: Incumbent) -> Self {
+        self.incumbent = Some(incumbent);
+        self
+    }
+
+    /// Sets number of runs (at least one run is always performed).
+    pub fn with_runs(mut self, runs: usize) -> Self {
+        self.runs = runs.max(1);
+        self
+    }
+
+    /// Sets base seed from which seeds of runs are derived.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets kinds of initial solutions assigned to runs.
+    /// Empty list leaves current kinds unchanged.
+    pub fn with_starts(mut self, starts: Vec<InitialSolution>) -> Self {
+        if !starts.is_empty() {
+            self.starts = starts;
+        }
+        self
+    }
+
+    /// Makes all runs start from `initial` schedule, differing only in seeds.
+    pub fn with_initial(mut self, initial: Schedule) -> Self {
+        self.initial = Some(initial);
+        self
+    }
+
+    /// Makes every run save checkpoints to its own file, see [`checkpoint_file`].
+    pub fn with_checkpoints(mut self, checkpoints: Checkpoints) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    /// Makes run of index `i` continue from `i`-th checkpoint, with seed taken from it.
+    /// Runs without checkpoint start from initial solutions.
+    pub fn with_resume(mut self, checkpoints: Vec<Checkpoint>) -> Self {
+        self.resume = checkpoints;
+        self
+    }
+
+    /// Makes logs of runs keep only events in which `interests` are taken, see [`Log::with_interests`].
+    pub fn with_interests(mut self, interests: Interests) -> Self {
+        self.interests = interests;
+        self
+    }
+
+    pub fn run(&self) -> MultiStartResult {
+        let incumbent = self
+            .incumbent
+            .clone()
+            .unwrap_or_else(|| Incumbent::new(self.case.lower_bound()));
+
+        let mut outcomes: Vec<(Schedule, RunStats, Log)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..self.runs)
+                .map(|run| {
+                    let incumbent = incumbent.clone();
+                    scope.spawn(move || self.single_run(run, incumbent))
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("Annealing run panicked."))
+                .collect()
+        });
+
+        // Ties are resolved in favour of the lowest run index.
+        let winner = outcomes
+            .iter()
+            .min_by_key(|(_, stats, _)| (stats.best_makespan, stats.run))
+            .map(|(_, stats, _)| stats.run)
+            .unwrap();
+
+        let runs = outcomes.iter().map(|(_, stats, _)| stats.clone()).collect();
+        let (best, _, log) = outcomes.swap_remove(winner);
+
+        MultiStartResult {
+            best,
+            winner,
+            runs,
+            log,
+        }
+    }
+
+    fn single_run(&self, run: usize, incumbent: Incumbent) -> (Schedule, RunStats, Log) {
+        let resume = self.resume.get(run).cloned();
+        let seed = resume
+            .as_ref()
+            .map_or(self.seed.wrapping_add(run as u64), Checkpoint::seed);
+        let (start, initial_solution) = match &self.initial {
+            Some(initial) => (None, initial.clone()),
+            None => {
+                let start = self.starts[run % self.starts.len()];
+                (Some(start), start.build(&self.case, &mut ChaCha12Rng::seed_from_u64(seed)))
+            }
+        };
+        let initial_makespan = initial_solution.makespan().unwrap();
+
+        let params = SimulatedAnnealingParams {
+            initial_solution,
+            seed,
+            ..self.params.clone()
+        };
+
+        let mut log = Log::new().with_interests(self.interests).with_capacity(RUN_LOG_CAPACITY);
+        let mut solution = Solution::new(params)
+            .with_budget(self.budget.clone())
+            .with_incumbent(incumbent);
+        if let Some(checkpoints) = &self.checkpoints {
+            solution = solution.with_checkpoints(Checkpoints {
+                path: checkpoint_file(&checkpoints.path, run),
+                interval: checkpoints.interval,
+            });
+        }
+        if let Some(checkpoint) = resume {
+            solution = solution.with_resume(checkpoint);
+        }
+        let best = solution.run(&mut log);
+
+        let stats = RunStats {
+            run,
+            seed,
+            initial: start,
+            initial_makespan,
+            best_makespan: best.makespan().unwrap(),
+            iterations: solution.iterations(),
+            elapsed: solution.elapsed(),
+            termination: solution.termination(),
+        };
+
+        (best, stats, log)
+    }
+}
+
+/// Returns checkpoint file of run of index `run`, i.e. `path` with run index appended.
+pub fn checkpoint_file(path: &Path, run: usize) -> PathBuf {
+    let mut file = path.as_os_str().to_owned();
+    file.push(format!(".{}", run));
+    file.into()
+}
+
+/// Multi-start Simulated Annealing as a solver.
+///
+/// Observer receives log of the winning run only.
+pub struct MultiStartAnnealing {
+    params: SimulatedAnnealingParams,
+    runs: usize,
+    starts: Vec<InitialSolution>,
+    checkpoints: Option<Checkpoints>,
+    resume: Vec<Checkpoint>,
+}
+
+impl MultiStartAnnealing {
+    pub fn new(params: SimulatedAnnealingParams, runs: usize, starts: Vec<InitialSolution>) -> Self {
+        Self {
+            params,
+            runs,
+            starts,
+            checkpoints: None,
+            resume: Vec::new(),
+        }
+    }
+
+    /// See [`MultiStart::with_checkpoints`].
+    pub fn with_checkpoints(mut self, checkpoints: Checkpoints) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    /// See [`MultiStart::with_resume`].
+    pub fn with_resume(mut self, checkpoints: Vec<Checkpoint>) -> Self {
+        self.resume = checkpoints;
+        self
+    }
+}
+
+impl Solver for MultiStartAnnealing {
+    fn name(&self) -> &'static str {
+        "multistart"
+    }
+
+    /// When `initial` is given all runs start from it, otherwise they build their own initial solutions.
+    fn solve(&self, case: &Case, initial: Option<Schedule>, budget: &Budget, observer: &mut dyn Observer) -> Outcome {
+        let mut multistart = MultiStart::new(case.clone(), self.params.clone())
+            .with_runs(self.runs)
+            .with_starts(self.starts.clone())
+            .with_budget(budget.clone())
+            .with_resume(self.resume.clone())
+            .with_interests(Interests::of(observer));
+        if let Some(checkpoints) = &self.checkpoints {
+            multistart = multistart.with_checkpoints(checkpoints.clone());
+        }
+        if let Some(initial) = initial {
+            multistart = multistart.with_initial(initial);
+        }
+        let result = multistart.run();
+
+        result.log.replay(observer);
+
+        let mut details: Vec<String> = result.runs.iter().map(ToString::to_string).collect();
+        details.push(format!("Best solution found by run {}", result.winner));
+
+        Outcome {
+            stats: SolverStats {
+                iterations: result.runs.iter().map(|stats| stats.iterations).sum(),
+                elapsed: result.runs.iter().map(|stats| stats.elapsed).max().unwrap_or_default(),
+                details,
+                optimal: result.best.makespan().unwrap() <= case.lower_bound(),
+                termination: result.runs[result.winner].termination,
+            },
+            schedule: result.best,
+        }
+    }
+}
+
+pub fn solver(settings: &Settings) -> Result<Box<dyn Solver>, String> {
+    let params = SimulatedAnnealingParams {
+        seed: settings.seed,
+        max_simulation_time: settings.kill_time,
+        ..settings.annealing.apply(SimulatedAnnealingParams::default())
+    };
+    let starts = if settings.starts.is_empty() {
+        vec![InitialSolution::Random, InitialSolution::Lpt, InitialSolution::PerturbedLpt]
+    } else {
+        settings.starts.clone()
+    };
+
+    let mut multistart = MultiStartAnnealing::new(params, settings.runs, starts);
+    if let Some(path) = &settings.checkpoint {
+        multistart = multistart.with_checkpoints(Checkpoints {
+            path: path.into(),
+            interval: settings.checkpoint_interval,
+        });
+    }
+    if let Some(path) = &settings.resume {
+        let checkpoints = (0..settings.runs)
+            .map(|run| {
+                let checkpoint = read_checkpoint(checkpoint_file(Path::new(path), run))?;
+                checkpoint.fits_params(&multistart.params)?;
+                Ok(checkpoint)
+            })
+            .collect::<Result<_, String>>()?;
+        multistart = multistart.with_resume(checkpoints);
+    }
+
+    Ok(Box::new(multistart))
+}
+
+#[cfg(test)]
+mod test_multistart {
+    use super::*;
+    use crate::observers::Events;
+    use crate::simulated_annealing::{Acceptance, Neighbourhood, Reduction};
+    use crate::solver::Event;
+    use crate::utils::Task;
+
+    fn params() -> SimulatedAnnealingParams {
+        SimulatedAnnealingParams {
+            initial_solution: Schedule::new(),
+            initial_temperature: 10.0,
+            final_temperature: 0.1,
+            reduction_rule: Reduction::Geometric(0.9),
+            neighbourhood: Neighbourhood::default(),
+            acceptance: Acceptance::Metropolis,
+            iterations_per_temperature: 10,
+            max_changeless_iterations: 1000,
+            max_simulation_time: 5,
+            seed: 0,
+            calibration: None,
+            restart: None,
+        }
+    }
+
+    #[test]
+    fn test_initial_solution_from_str() {
+        for start in &[InitialSolution::Random, InitialSolution::Lpt, InitialSolution::PerturbedLpt] {
+            assert_eq!(start.to_string().parse::<InitialSolution>(), Ok(*start));
+        }
+        assert!("best".parse::<InitialSolution>().is_err());
+    }
+
+    #[test]
+    fn test_runs_collect_stats() {
+        let mut case = Case::new().with_cores(3);
+        case.add_tasks((1..20).map(Task::with_length).collect());
+
+        let result = MultiStart::new(case.clone(), params())
+            .with_runs(3)
+            .with_seed(42)
+            .with_starts(vec![InitialSolution::Random, InitialSolution::PerturbedLpt])
+            .run();
+
+        assert_eq!(result.runs.len(), 3);
+        assert_eq!(result.runs[1].seed, 43);
+        assert_eq!(result.runs[2].initial, Some(InitialSolution::Random));
+        assert!(result.runs.iter().all(|stats| stats.best_makespan >= case.lower_bound()));
+        assert_eq!(result.best.makespan().unwrap(), result.runs[result.winner].best_makespan);
+        assert!(!result.log.is_empty());
+    }
+
+    #[test]
+    fn test_stops_at_lower_bound() {
+        // LPT is optimal here, so runs starting from it stop right away.
+        let mut case = Case::new().with_cores(2);
+        case.add_tasks(vec![Task::with_length(4), Task::with_length(4)]);
+
+        let result = MultiStart::new(case, params())
+            .with_runs(2)
+            .with_starts(vec![InitialSolution::Lpt])
+            .run();
+
+        assert_eq!(result.best.makespan().unwrap(), 4);
+        assert!(result.runs.iter().all(|stats| stats.iterations == 0));
+    }
+
+    #[test]
+    fn test_runs_start_from_given_schedule() {
+        let mut case = Case::new().with_cores(3);
+        case.add_tasks((1..20).map(Task::with_length).collect());
+        let initial = greedy::schedule(&case);
+
+        let result = MultiStart::new(case, params())
+            .with_runs(2)
+            .with_initial(initial.clone())
+            .run();
+
+        for stats in &result.runs {
+            assert_eq!(stats.initial, None);
+            assert_eq!(stats.initial_makespan, initial.makespan().unwrap());
+        }
+        assert!(result.best.makespan().unwrap() <= initial.makespan().unwrap());
+    }
+
+    #[test]
+    fn test_observer_receives_events_of_winner() {
+        let mut case = Case::new().with_cores(3);
+        case.add_tasks((1..20).map(Task::with_length).collect());
+        let solver = MultiStartAnnealing::new(params(), 2, vec![InitialSolution::Random]);
+        let mut events = Events::new();
+
+        let outcome = solver.solve(&case, None, &Budget::with_time(Duration::from_secs(5)).with_iterations(50), &mut events);
+
+        assert_eq!(events.count(|event| *event == Event::Started), 1);
+        let moves = events.count(|event| matches!(event, Event::Moved { .. }));
+        assert!(moves > 0 && moves <= 50);
+        assert_eq!(events.count(|event| matches!(event, Event::Finished { .. })), 1);
+        let last = events.occurrences().last().unwrap();
+        assert_eq!(last.best, outcome.schedule.makespan().unwrap());
+    }
+}
@@ -0,0 +1,150 @@
+//! Sinks for solver events, besides log file (`Serializer`)
+//! and terminal progress (`Printer`).
+
+use crate::solver::{Event, EventKind, Interest, Observer, Progress};
+use std::time::SystemTime;
+
+/// Passes everything to all subscribed observers, in order of subscription.
+#[derive(Default)]
+pub struct Observers<'a> {
+    sinks: Vec<&'a mut dyn Observer>,
+}
+
+impl<'a> Observers<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, sink: &'a mut dyn Observer) -> Self {
+        self.subscribe(sink);
+        self
+    }
+
+    pub fn subscribe(&mut self, sink: &'a mut dyn Observer) {
+        self.sinks.push(sink);
+    }
+}
+
+impl Observer for Observers<'_> {
+    fn record(&mut self, iteration: u64, makespan: u128) {
+        for sink in &mut self.sinks {
+            sink.record(iteration, makespan);
+        }
+    }
+
+    fn record_at(&mut self, iteration: u64, makespan: u128, time: SystemTime) {
+        for sink in &mut self.sinks {
+            sink.record_at(iteration, makespan, time);
+        }
+    }
+
+    fn comment(&mut self, comment: &str) {
+        for sink in &mut self.sinks {
+            sink.comment(comment);
+        }
+    }
+
+    fn event(&mut self, event: Event, progress: &Progress<'_>) {
+        for sink in &mut self.sinks {
+            sink.event(event, progress);
+        }
+    }
+
+    fn finish(&mut self) {
+        for sink in &mut self.sinks {
+            sink.finish();
+        }
+    }
+
+    fn interest(&self, kind: EventKind) -> Interest {
+        self.sinks.iter().map(|sink| sink.interest(kind)).max().unwrap_or(Interest::Ignored)
+    }
+}
+
+/// Calls closure on every event, e.g. `Hook(|event, progress| ...)`.
+pub struct Hook<F>(pub F);
+
+impl<F: FnMut(Event, &Progress<'_>)> Observer for Hook<F> {
+    fn event(&mut self, event: Event, progress: &Progress<'_>) {
+        (self.0)(event, progress)
+    }
+}
+
+/// Event which happened at given iteration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Occurrence {
+    pub iteration: u64,
+    pub event: Event,
+    pub makespan: u128,
+    pub best: u128,
+}
+
+/// Collects events in memory.
+#[derive(Debug, Clone, Default)]
+pub struct Events {
+    occurrences: Vec<Occurrence>,
+}
+
+impl Events {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn occurrences(&self) -> &[Occurrence] {
+        &self.occurrences
+    }
+
+    /// Returns how many collected events satisfy `predicate`.
+    pub fn count<P: Fn(&Event) -> bool>(&self, predicate: P) -> usize {
+        self.occurrences.iter().filter(|occurrence| predicate(&occurrence.event)).count()
+    }
+}
+
+impl Observer for Events {
+    fn event(&mut self, event: Event, progress: &Progress<'_>) {
+        self.occurrences.push(Occurrence {
+            iteration: progress.iteration,
+            event,
+            makespan: progress.makespan,
+            best: progress.best,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test_observers {
+    use super::*;
+    use crate::solver::Termination;
+    use crate::utils::{Assignment, Core, Schedule, Task};
+
+    #[test]
+    fn test_all_sinks_receive_events() {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from(vec![Task::with_length(4)]));
+        let solution = Assignment::from(&schedule);
+        let progress = Progress {
+            iteration: 3,
+            temperature: 1.5,
+            makespan: 4,
+            best: 4,
+            solution: Some(&solution),
+        };
+
+        let mut events = Events::new();
+        let mut accepted = 0;
+        let mut hook = Hook(|event, _: &Progress<'_>| {
+            if event == (Event::Moved { accepted: true }) {
+                accepted += 1;
+            }
+        });
+        let mut observers = Observers::new().with(&mut events).with(&mut hook);
+        observers.event(Event::Moved { accepted: true }, &progress);
+        observers.event(Event::Moved { accepted: false }, &progress);
+        observers.event(Event::Finished { termination: Termination::Iterations }, &progress);
+
+        assert_eq!(accepted, 1);
+        assert_eq!(events.occurrences().len(), 3);
+        assert_eq!(events.occurrences()[0].iteration, 3);
+        assert_eq!(events.count(|event| matches!(event, Event::Moved { .. })), 2);
+    }
+}
@@ -0,0 +1,453 @@
+//! Convergence plots of logged runs, written as SVG.
+
+use serde_json::Value;
+use std::{error::Error, fmt, fs, path::Path, str::FromStr};
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 450.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 50.0;
+/// Longer runs are thinned out, so plots stay small.
+const MAX_POINTS: usize = 2000;
+const COLORS: [&str; 8] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+/// Quantity on horizontal axis of plots.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum Axis {
+    #[default]
+    Iteration,
+    /// Seconds since run started, logged only in JSON Lines.
+    Time,
+}
+
+impl fmt::Display for Axis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Iteration => write!(f, "iteration"),
+            Self::Time => write!(f, "time"),
+        }
+    }
+}
+
+impl FromStr for Axis {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "iteration" => Ok(Self::Iteration),
+            "time" => Ok(Self::Time),
+            _ => Err(format!("Unknown axis `{}`.", name)),
+        }
+    }
+}
+
+/// Logged state of solver after single iteration.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Point {
+    pub iteration: u64,
+    /// Seconds since first record of run, if it was logged.
+    pub time: Option<f64>,
+    pub makespan: u128,
+    pub best: u128,
+}
+
+/// Run read from log file.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Run {
+    pub name: String,
+    pub points: Vec<Point>,
+}
+
+impl Run {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            points: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, iteration: u64, timestamp: Option<f64>, makespan: u128, best: Option<u128>) {
+        let previous = self.points.last().map_or(makespan, |point| point.best);
+        self.points.push(Point {
+            iteration,
+            time: timestamp,
+            makespan,
+            best: best.unwrap_or(makespan).min(previous),
+        });
+    }
+
+    /// Makes times relative to the first record.
+    fn align(mut self) -> Self {
+        if let Some(start) = self.points.first().and_then(|point| point.time) {
+            for point in &mut self.points {
+                point.time = point.time.map(|time| time - start);
+            }
+        }
+        self
+    }
+
+    /// Reads all runs logged in `source`, in either log format. Runs are named after `name` of log.
+    pub fn read_all(source: &str, name: &str) -> Result<Vec<Run>, String> {
+        let first = source.lines().map(str::trim).find(|line| !line.is_empty());
+        let runs = match first {
+            Some(line) if line.starts_with('{') => Self::read_json_lines(source, name)?,
+            _ => Self::read_csv(source, name)?,
+        };
+        Ok(runs.into_iter().filter(|run| !run.points.is_empty()).map(Run::align).collect())
+    }
+
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Vec<Run>, Box<dyn Error>> {
+        let path = path.as_ref();
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        Ok(Self::read_all(&fs::read_to_string(path)?, &name)?)
+    }
+
+    fn read_csv(source: &str, name: &str) -> Result<Vec<Run>, String> {
+        let mut runs = vec![Run::new(format!("{} run 1", name))];
+        for (number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line == "---" {
+                runs.push(Run::new(format!("{} run {}", name, runs.len() + 1)));
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || format!("Invalid record `{}` in line {}.", line, number + 1);
+            let (iteration, makespan) = line.split_once(',').ok_or_else(invalid)?;
+            let iteration = iteration.trim().parse().map_err(|_| invalid())?;
+            let makespan = makespan.trim().parse().map_err(|_| invalid())?;
+            runs.last_mut().unwrap().add(iteration, None, makespan, None);
+        }
+        Ok(runs)
+    }
+
+    fn read_json_lines(source: &str, name: &str) -> Result<Vec<Run>, String> {
+        let mut runs: Vec<Run> = Vec::new();
+        let mut ended = true;
+        for (number, line) in source.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = || format!("Invalid record in line {}.", number + 1);
+            let value: Value = serde_json::from_str(line).map_err(|_| invalid())?;
+            match value["type"].as_str() {
+                Some("header") => {
+                    runs.push(Run::new(format!(
+                        "{} on {} (seed {})",
+                        value["solver"].as_str().unwrap_or("solver"),
+                        value["instance"].as_str().unwrap_or(name),
+                        value["seed"]
+                    )));
+                    ended = false;
+                }
+                Some("record") => {
+                    if ended {
+                        runs.push(Run::new(format!("{} run {}", name, runs.len() + 1)));
+                        ended = false;
+                    }
+                    let makespan = value["makespan"].as_u64().ok_or_else(invalid)?;
+                    runs.last_mut().unwrap().add(
+                        value["iteration"].as_u64().ok_or_else(invalid)?,
+                        value["timestamp"].as_f64(),
+                        u128::from(makespan),
+                        value["best"].as_u64().map(u128::from),
+                    );
+                }
+                Some("end") => ended = true,
+                Some(_) => {}
+                None => return Err(invalid()),
+            }
+        }
+        Ok(runs)
+    }
+}
+
+/// Line drawn on plot.
+struct Series {
+    label: String,
+    color: &'static str,
+    points: Vec<(f64, f64)>,
+}
+
+/// Draws convergence of runs.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Plot {
+    axis: Axis,
+    lower_bound: Option<u128>,
+}
+
+impl Plot {
+    pub fn new(axis: Axis) -> Self {
+        Self {
+            axis,
+            lower_bound: None,
+        }
+    }
+
+    /// Draws horizontal line at lower bound of makespan.
+    pub fn with_lower_bound(mut self, lower_bound: u128) -> Self {
+        self.lower_bound = Some(lower_bound);
+        self
+    }
+
+    /// Returns SVG with current and best makespan of `run`.
+    pub fn run(&self, run: &Run) -> Result<String, String> {
+        let series = vec![
+            Series {
+                label: "current".to_string(),
+                color: "#9ecae1",
+                points: self.coordinates(run, |point| point.makespan)?,
+            },
+            Series {
+                label: "best".to_string(),
+                color: COLORS[0],
+                points: self.coordinates(run, |point| point.best)?,
+            },
+        ];
+        Ok(self.render(&run.name, &series))
+    }
+
+    /// Returns SVG comparing best makespan of `runs` on the same axes.
+    pub fn overlay(&self, runs: &[Run]) -> Result<String, String> {
+        let series = runs
+            .iter()
+            .enumerate()
+            .map(|(idx, run)| {
+                Ok(Series {
+                    label: run.name.clone(),
+                    color: COLORS[idx % COLORS.len()],
+                    points: self.coordinates(run, |point| point.best)?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(self.render("Best makespan", &series))
+    }
+
+    fn coordinates<F: Fn(&Point) -> u128>(&self, run: &Run, value: F) -> Result<Vec<(f64, f64)>, String> {
+        let stride = run.points.len().div_ceil(MAX_POINTS).max(1);
+        let last = run.points.len() - 1;
+        run.points
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| idx % stride == 0 || *idx == last)
+            .map(|(_, point)| {
+                let x = match self.axis {
+                    Axis::Iteration => point.iteration as f64,
+                    Axis::Time => point
+                        .time
+                        .ok_or_else(|| format!("Run `{}` has no timestamps, time axis needs JSON Lines log.", run.name))?,
+                };
+                Ok((x, value(point) as f64))
+            })
+            .collect()
+    }
+
+    fn render(&self, title: &str, series: &[Series]) -> String {
+        let xs = series.iter().flat_map(|series| series.points.iter().map(|point| point.0));
+        let ys = series.iter().flat_map(|series| series.points.iter().map(|point| point.1));
+        let (x_min, x_max) = bounds(xs);
+        let (y_min, y_max) = bounds(ys.chain(self.lower_bound.map(|bound| bound as f64)));
+        let (x_ticks, x_min, x_max) = ticks(x_min, x_max);
+        let (y_ticks, y_min, y_max) = ticks(y_min, y_max);
+
+        let (x_decimals, y_decimals) = (decimals(&x_ticks), decimals(&y_ticks));
+        let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+        let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+        let x = |value: f64| MARGIN_LEFT + (value - x_min) / (x_max - x_min) * plot_width;
+        let y = |value: f64| MARGIN_TOP + (y_max - value) / (y_max - y_min) * plot_height;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n\
+             <rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n\
+             <text x=\"{cx}\" y=\"22\" text-anchor=\"middle\" font-size=\"15\">{title}</text>\n",
+            w = WIDTH,
+            h = HEIGHT,
+            cx = WIDTH / 2.0,
+            title = escape(title)
+        );
+        for &tick in &x_ticks {
+            svg += &format!(
+                "<line x1=\"{x:.1}\" y1=\"{top}\" x2=\"{x:.1}\" y2=\"{bottom}\" stroke=\"#eeeeee\"/>\n\
+                 <text x=\"{x:.1}\" y=\"{label}\" text-anchor=\"middle\">{value:.decimals$}</text>\n",
+                x = x(tick),
+                top = MARGIN_TOP,
+                bottom = HEIGHT - MARGIN_BOTTOM,
+                label = HEIGHT - MARGIN_BOTTOM + 16.0,
+                value = tick,
+                decimals = x_decimals
+            );
+        }
+        for &tick in &y_ticks {
+            svg += &format!(
+                "<line x1=\"{left}\" y1=\"{y:.1}\" x2=\"{right}\" y2=\"{y:.1}\" stroke=\"#eeeeee\"/>\n\
+                 <text x=\"{label}\" y=\"{y:.1}\" text-anchor=\"end\" dominant-baseline=\"middle\">{value:.decimals$}</text>\n",
+                y = y(tick),
+                left = MARGIN_LEFT,
+                right = WIDTH - MARGIN_RIGHT,
+                label = MARGIN_LEFT - 6.0,
+                value = tick,
+                decimals = y_decimals
+            );
+        }
+        svg += &format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\"/>\n\
+             <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n\
+             <text x=\"16\" y=\"{}\" text-anchor=\"middle\" transform=\"rotate(-90 16 {})\">makespan</text>\n",
+            MARGIN_LEFT,
+            MARGIN_TOP,
+            plot_width,
+            plot_height,
+            MARGIN_LEFT + plot_width / 2.0,
+            HEIGHT - 12.0,
+            match self.axis {
+                Axis::Iteration => "iteration",
+                Axis::Time => "time [s]",
+            },
+            MARGIN_TOP + plot_height / 2.0,
+            MARGIN_TOP + plot_height / 2.0
+        );
+
+        if let Some(bound) = self.lower_bound {
+            svg += &format!(
+                "<line x1=\"{}\" y1=\"{y:.1}\" x2=\"{}\" y2=\"{y:.1}\" stroke=\"black\" stroke-dasharray=\"6 4\"/>\n",
+                MARGIN_LEFT,
+                WIDTH - MARGIN_RIGHT,
+                y = y(bound as f64)
+            );
+        }
+        for series in series {
+            let points: Vec<String> = series
+                .points
+                .iter()
+                .map(|&(px, py)| format!("{:.1},{:.1}", x(px), y(py)))
+                .collect();
+            svg += &format!(
+                "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\" points=\"{}\"/>\n",
+                series.color,
+                points.join(" ")
+            );
+        }
+
+        let legend = series
+            .iter()
+            .map(|series| (series.label.clone(), series.color, ""))
+            .chain(self.lower_bound.map(|bound| (format!("lower bound {}", bound), "black", "6 4")));
+        for (idx, (label, color, dashes)) in legend.enumerate() {
+            let top = MARGIN_TOP + 14.0 + 16.0 * idx as f64;
+            svg += &format!(
+                "<line x1=\"{}\" y1=\"{top}\" x2=\"{}\" y2=\"{top}\" stroke=\"{}\" stroke-width=\"2\" stroke-dasharray=\"{}\"/>\n\
+                 <text x=\"{}\" y=\"{top}\" dominant-baseline=\"middle\">{}</text>\n",
+                WIDTH - MARGIN_RIGHT - 230.0,
+                WIDTH - MARGIN_RIGHT - 210.0,
+                color,
+                dashes,
+                WIDTH - MARGIN_RIGHT - 204.0,
+                escape(&label),
+                top = top
+            );
+        }
+        svg + "</svg>\n"
+    }
+}
+
+fn bounds<I: Iterator<Item = f64>>(values: I) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+        (min.min(value), max.max(value))
+    })
+}
+
+/// Returns round tick values covering `min..=max`, with bounds extended to the outer ticks.
+pub(crate) fn ticks(min: f64, max: f64) -> (Vec<f64>, f64, f64) {
+    let (min, max) = if max > min { (min, max) } else { (min - 1.0, min + 1.0) };
+    let rough = (max - min) / 5.0;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|factor| factor * magnitude)
+        .find(|&step| step >= rough)
+        .unwrap();
+    let first = (min / step).floor() * step;
+    let last = (max / step).ceil() * step;
+    let count = ((last - first) / step).round() as usize;
+    let ticks = (0..=count).map(|idx| first + step * idx as f64).collect();
+    (ticks, first, last)
+}
+
+/// Returns number of decimal places needed to tell `ticks` apart.
+pub(crate) fn decimals(ticks: &[f64]) -> usize {
+    match ticks {
+        [first, second, ..] if second - first < 1.0 => (-(second - first).log10()).ceil() as usize,
+        _ => 0,
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test_plot {
+    use super::*;
+
+    #[test]
+    fn test_read_csv() {
+        let runs = Run::read_all("# seed 1\n0,10\n1,12\n2,8\n---\n0,9\n# restart\n1,7\n---\n", "data").unwrap();
+
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].name, "data run 1");
+        let best: Vec<u128> = runs[0].points.iter().map(|point| point.best).collect();
+        assert_eq!(best, vec![10, 10, 8]);
+        assert_eq!(runs[1].points[1].makespan, 7);
+        assert!(Run::read_all("0;10\n", "data").is_err());
+    }
+
+    #[test]
+    fn test_read_json_lines() {
+        let source = "{\"type\":\"header\",\"instance\":\"t.case\",\"solver\":\"sa\",\"seed\":3}\n\
+                      {\"type\":\"record\",\"timestamp\":100.5,\"iteration\":0,\"makespan\":10,\"best\":10}\n\
+                      {\"type\":\"record\",\"timestamp\":101.0,\"iteration\":1,\"makespan\":12,\"best\":9}\n\
+                      {\"type\":\"comment\",\"text\":\"restart\"}\n\
+                      {\"type\":\"end\"}\n";
+        let runs = Run::read_all(source, "data").unwrap();
+
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].name, "sa on t.case (seed 3)");
+        assert_eq!(runs[0].points[0].time, Some(0.0));
+        assert_eq!(runs[0].points[1].time, Some(0.5));
+        assert_eq!(runs[0].points[1].best, 9);
+    }
+
+    #[test]
+    fn test_render() {
+        let runs = Run::read_all("0,10\n1,12\n2,8\n---\n0,9\n1,7\n", "data").unwrap();
+        let plot = Plot::new(Axis::Iteration).with_lower_bound(6);
+
+        let svg = plot.run(&runs[0]).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains("lower bound 6"));
+
+        let svg = plot.overlay(&runs).unwrap();
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains("data run 2"));
+
+        assert!(Plot::new(Axis::Time).run(&runs[0]).is_err());
+    }
+
+    #[test]
+    fn test_ticks() {
+        let (ticks, min, max) = ticks(3.0, 97.0);
+        assert_eq!(ticks, vec![0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+        assert_eq!((min, max), (0.0, 100.0));
+        assert_eq!(decimals(&ticks), 0);
+
+        let (ticks, _, _) = super::ticks(0.0, 0.9);
+        assert_eq!(decimals(&ticks), 1);
+        assert_eq!(format!("{:.*}", decimals(&ticks), ticks[3]), "0.6");
+    }
+}
@@ -0,0 +1,297 @@
+use crate::solver::{
+    self, Budget, Event, EventKind, Interest, Interests, Log, Observer, Outcome, Progress, Solver, SolverStats,
+    Termination, RUN_LOG_CAPACITY,
+};
+use crate::utils::{Case, Incumbent, Schedule, Settings};
+use std::{
+    fmt, thread,
+    time::{Duration, SystemTime},
+};
+
+/// Solvers raced by portfolio when none are configured.
+const DEFAULT_MEMBERS: &[&str] = &["lpt", "sa", "multistart"];
+
+/// Result of single solver raced in portfolio.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub solver: &'static str,
+    pub makespan: u128,
+    /// Time since portfolio start after which solver found its best schedule.
+    pub found_at: Duration,
+    /// Time since portfolio start after which solver finished.
+    pub finished_at: Duration,
+    /// Limit which stopped iterative solver.
+    pub termination: Option<Termination>,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} found after {:.2}s, finished after {:.2}s",
+            self.solver,
+            self.makespan,
+            self.found_at.as_secs_f64(),
+            self.finished_at.as_secs_f64()
+        )?;
+        if let Some(termination) = self.termination {
+            write!(f, ", stopped by {}", termination)?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of portfolio race.
+pub struct PortfolioResult {
+    /// Best schedule found by any solver.
+    pub best: Schedule,
+    /// Index of solver which found `best`.
+    pub winner: usize,
+    /// Results of every solver, ordered as solvers were given.
+    pub entries: Vec<Entry>,
+    /// Whether `best` reaches case's lower bound.
+    pub optimal: bool,
+    /// Log of the winning solver.
+    pub log: Log,
+}
+
+/// Collects log of single solver and notes when its best makespan was found.
+/// Times are taken from records, so they stay right for solvers which replay their logs after finishing.
+struct Tracker {
+    start: SystemTime,
+    incumbent: Incumbent,
+    best: u128,
+    found_at: Duration,
+    finished_at: Duration,
+    log: Log,
+}
+
+impl Observer for Tracker {
+    fn record(&mut self, iteration: u64, makespan: u128) {
+        self.record_at(iteration, makespan, SystemTime::now());
+    }
+
+    fn record_at(&mut self, iteration: u64, makespan: u128, time: SystemTime) {
+        if makespan < self.best {
+            self.best = makespan;
+            self.found_at = time.duration_since(self.start).unwrap_or_default();
+            self.incumbent.offer(makespan);
+        }
+        self.log.record_at(iteration, makespan, time);
+    }
+
+    fn comment(&mut self, comment: &str) {
+        self.log.comment(comment);
+    }
+
+    fn event(&mut self, event: Event, progress: &Progress<'_>) {
+        self.log.event(event, progress);
+    }
+
+    fn finish(&mut self) {
+        self.finished_at = self.start.elapsed().unwrap_or_default();
+    }
+
+    fn interest(&self, kind: EventKind) -> Interest {
+        self.log.interest(kind)
+    }
+}
+
+/// Races several solvers concurrently on the same case.
+///
+/// All solvers share wall-clock budget and best found makespan,
+/// so every one of them stops as soon as any proves optimality against case's lower bound.
+pub struct Portfolio {
+    solvers: Vec<Box<dyn Solver>>,
+}
+
+impl Portfolio {
+    pub fn new(solvers: Vec<Box<dyn Solver>>) -> Self {
+        Self { solvers }
+    }
+
+    pub fn run(&self, case: &Case, initial: Option<Schedule>, budget: &Budget) -> PortfolioResult {
+        self.race(case, initial, budget, Interests::default())
+    }
+
+    /// Runs solvers, logging events in which `interests` are taken, see [`Log::with_interests`].
+    fn race(&self, case: &Case, initial: Option<Schedule>, budget: &Budget, interests: Interests) -> PortfolioResult {
+        let incumbent = budget
+            .incumbent()
+            .cloned()
+            .unwrap_or_else(|| Incumbent::new(case.lower_bound()));
+        let budget = budget.clone().with_incumbent(incumbent.clone());
+        let start = SystemTime::now();
+
+        let mut outcomes: Vec<(Outcome, Tracker)> = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .solvers
+                .iter()
+                .map(|solver| {
+                    let (budget, initial) = (&budget, initial.clone());
+                    let mut tracker = Tracker {
+                        start,
+                        incumbent: incumbent.clone(),
+                        best: u128::MAX,
+                        found_at: Duration::default(),
+                        finished_at: Duration::default(),
+                        log: Log::new().with_interests(interests).with_capacity(RUN_LOG_CAPACITY),
+                    };
+                    scope.spawn(move || (solver.solve(case, initial, budget, &mut tracker), tracker))
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("Portfolio solver panicked."))
+                .collect()
+        });
+
+        let entries: Vec<Entry> = self
+            .solvers
+            .iter()
+            .zip(&outcomes)
+            .map(|(solver, (outcome, tracker))| Entry {
+                solver: solver.name(),
+                makespan: outcome.schedule.makespan().unwrap(),
+                found_at: tracker.found_at,
+                finished_at: tracker.finished_at,
+                termination: outcome.stats.termination,
+            })
+            .collect();
+
+        // The earliest find wins among equally good schedules.
+        let winner = entries
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, entry)| (entry.makespan, entry.found_at))
+            .map(|(idx, _)| idx)
+            .unwrap();
+
+        let (outcome, tracker) = outcomes.swap_remove(winner);
+
+        PortfolioResult {
+            optimal: outcome.schedule.makespan().unwrap() <= case.lower_bound(),
+            best: outcome.schedule,
+            winner,
+            entries,
+            log: tracker.log,
+        }
+    }
+}
+
+impl Solver for Portfolio {
+    fn name(&self) -> &'static str {
+        "portfolio"
+    }
+
+    /// Observer receives log of the winning solver only.
+    fn solve(&self, case: &Case, initial: Option<Schedule>, budget: &Budget, observer: &mut dyn Observer) -> Outcome {
+        let result = self.race(case, initial, budget, Interests::of(observer));
+
+        result.log.replay(observer);
+
+        let winner = &result.entries[result.winner];
+        let mut details: Vec<String> = result.entries.iter().map(ToString::to_string).collect();
+        details.push(format!(
+            "Final schedule produced by {} after {:.2}s{}",
+            winner.solver,
+            winner.found_at.as_secs_f64(),
+            if result.optimal { " (proven optimal)" } else { "" }
+        ));
+
+        Outcome {
+            schedule: result.best,
+            stats: SolverStats {
+                iterations: 0,
+                elapsed: result.entries.iter().map(|entry| entry.finished_at).max().unwrap_or_default(),
+                details,
+                optimal: result.optimal,
+                termination: result.entries[result.winner].termination,
+            },
+        }
+    }
+}
+
+pub fn solver(settings: &Settings) -> Result<Box<dyn Solver>, String> {
+    // Members would overwrite each other's checkpoints.
+    if settings.checkpoint.is_some() || settings.resume.is_some() {
+        return Err("Portfolio doesn't support checkpoints.".to_string());
+    }
+    let members: Vec<&str> = if settings.portfolio.is_empty() {
+        DEFAULT_MEMBERS.to_vec()
+    } else {
+        settings.portfolio.iter().map(String::as_str).collect()
+    };
+
+    // Members get distinct seeds, so the same solver raced twice explores differently.
+    let solvers = members
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| match name {
+            "portfolio" => Err("Portfolio can't race itself.".to_string()),
+            _ => solver::create(
+                name,
+                &Settings {
+                    seed: settings.seed.wrapping_add(idx as u64),
+                    ..settings.clone()
+                },
+            ),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Box::new(Portfolio::new(solvers)))
+}
+
+#[cfg(test)]
+mod test_portfolio {
+    use super::*;
+    use crate::utils::Task;
+
+    #[test]
+    fn test_stops_when_optimal() {
+        // LPT is optimal here, so annealing stops as soon as LPT reports.
+        let mut case = Case::new().with_cores(2);
+        case.add_tasks((1..5).map(Task::with_length).collect());
+
+        let settings = Settings {
+            portfolio: vec!["sa".to_string(), "lpt".to_string()],
+            ..Settings::default()
+        };
+        let portfolio = solver(&settings).unwrap();
+        let outcome = portfolio.solve(&case, None, &Budget::with_time(Duration::from_secs(60)), &mut ());
+
+        assert_eq!(outcome.schedule.makespan().unwrap(), 5);
+        assert!(outcome.stats.elapsed < Duration::from_secs(60));
+        assert!(outcome.stats.details.last().unwrap().contains("(proven optimal)"));
+    }
+
+    #[test]
+    fn test_tracker_takes_times_from_records() {
+        let start = SystemTime::now();
+        let mut tracker = Tracker {
+            start,
+            incumbent: Incumbent::new(1),
+            best: u128::MAX,
+            found_at: Duration::default(),
+            finished_at: Duration::default(),
+            log: Log::new(),
+        };
+        // Replayed log reports records made long before replay.
+        tracker.record_at(0, 10, start + Duration::from_secs(1));
+        tracker.record_at(1, 8, start + Duration::from_secs(2));
+        tracker.record_at(2, 9, start + Duration::from_secs(3));
+
+        assert_eq!(tracker.best, 8);
+        assert_eq!(tracker.found_at, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_rejects_nested_portfolio() {
+        let settings = Settings {
+            portfolio: vec!["portfolio".to_string()],
+            ..Settings::default()
+        };
+        assert!(solver(&settings).is_err());
+    }
+}
@@ -1,18 +1,310 @@
+use crate::solver::{Event, EventKind, Interest, Observer, Progress};
 use crate::utils::Schedule;
-use colorful::{Colorful, RGB};
+use colorful::Colorful;
+use std::{
+    env, fmt,
+    io::{self, IsTerminal},
+    str::FromStr,
+};
+
+const COLORS: [(u8, u8, u8); 2] = [(154, 205, 50), (255, 215, 0)];
+const ASCII_FILLS: [char; 2] = ['#', '='];
+/// Width used when terminal width can't be detected.
+const DEFAULT_WIDTH: usize = 80;
+const MIN_BAR_WIDTH: usize = 10;
+
+/// What is printed inside blocks of tasks.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum TaskLabels {
+    #[default]
+    None,
+    /// Numbers of tasks, counted through cores in order.
+    Ids,
+    Lengths,
+}
+
+impl fmt::Display for TaskLabels {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Ids => write!(f, "ids"),
+            Self::Lengths => write!(f, "lengths"),
+        }
+    }
+}
+
+impl FromStr for TaskLabels {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "none" => Ok(Self::None),
+            "ids" => Ok(Self::Ids),
+            "lengths" => Ok(Self::Lengths),
+            _ => Err(format!("Unknown task labels `{}`.", name)),
+        }
+    }
+}
+
+/// Gantt chart drawn in terminal, every row scaled to makespan of schedule.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct TerminalGantt {
+    width: usize,
+    color: bool,
+    labels: TaskLabels,
+}
+
+impl TerminalGantt {
+    /// Fits chart to width of terminal, using colours only when standard output is a colour terminal.
+    pub fn detect() -> Self {
+        Self {
+            width: terminal_width().unwrap_or(DEFAULT_WIDTH),
+            color: color_terminal(),
+            labels: TaskLabels::None,
+        }
+    }
+
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_labels(mut self, labels: TaskLabels) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn render(&self, schedule: &Schedule) -> String {
+        let cores = schedule.cores();
+        let makespan = cores.iter().map(|core| core.working_time()).max().unwrap_or(0);
+        let number_width = cores.len().to_string().len();
+        let load_width = makespan.to_string().len();
+        let prefix_width = "Core  ".len() + number_width;
+        let suffix_width = " load , idle ".len() + 2 * load_width;
+        let frame = if self.color { '│' } else { '|' };
+        let bar_width = self
+            .width
+            .saturating_sub(prefix_width + suffix_width + 2)
+            .max(MIN_BAR_WIDTH);
+        let cell = |time: u128| (2 * time * bar_width as u128 + makespan).checked_div(2 * makespan).unwrap_or(0) as usize;
+
+        let mut chart = String::new();
+        let mut number = 0;
+        for (idx, core) in cores.iter().enumerate() {
+            chart += &format!("Core {:>width$} {}", idx + 1, frame, width = number_width);
+            let mut start = 0u128;
+            for (position, task) in core.get_tasks().iter().enumerate() {
+                number += 1;
+                let end = start + u128::from(task.length());
+                // Ends of blocks are rounded, not their widths, so rows don't drift.
+                let cells = cell(end) - cell(start);
+                let label = match self.labels {
+                    TaskLabels::None => String::new(),
+                    TaskLabels::Ids => format!("t{}", number),
+                    TaskLabels::Lengths => task.length().to_string(),
+                };
+                chart += &self.block(cells, &label, position);
+                start = end;
+            }
+            let load = core.working_time();
+            chart += &format!(
+                "{}{} load {:>width$}, idle {:>width$}\n",
+                " ".repeat(bar_width - cell(load)),
+                frame,
+                load,
+                makespan - load,
+                width = load_width
+            );
+        }
+        chart
+    }
+
+    fn block(&self, cells: usize, label: &str, position: usize) -> String {
+        let fill = if self.color { ' ' } else { ASCII_FILLS[position % 2] };
+        let text = if !label.is_empty() && label.len() <= cells {
+            let before = (cells - label.len()) / 2;
+            let after = cells - label.len() - before;
+            format!(
+                "{}{}{}",
+                fill.to_string().repeat(before),
+                label,
+                fill.to_string().repeat(after)
+            )
+        } else {
+            fill.to_string().repeat(cells)
+        };
+        if self.color && cells > 0 {
+            let (r, g, b) = COLORS[position % 2];
+            text.bg_rgb(r, g, b).rgb(0, 0, 0).to_string()
+        } else {
+            text
+        }
+    }
+}
 
 impl Schedule {
+    /// Prints Gantt chart of schedule fitted to terminal.
     pub fn print(&self) {
-        let s = "█";
-        let colors = vec![RGB::new(154, 205, 50), RGB::new(255, 215, 0)];
-
-        for core in self.cores() {
-            for (idx, task) in core.get_tasks().iter().enumerate() {
-                let length = (task.length() as f64 / core.working_time() as f64 * 180.0) as usize;
-                let (r, g, b) = colors.get(idx % 2).unwrap().unpack();
-                print!("{}", s.repeat(length).rgb(r, g, b));
+        print!("{}", TerminalGantt::detect().render(self));
+    }
+}
+
+/// Returns number of columns of terminal connected to standard output.
+#[cfg(unix)]
+fn terminal_width() -> Option<usize> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: `size` is valid for writes, ioctl fails harmlessly when output isn't a terminal.
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    if result == 0 && size.ws_col > 0 {
+        return Some(usize::from(size.ws_col));
+    }
+    columns()
+}
+
+#[cfg(not(unix))]
+fn terminal_width() -> Option<usize> {
+    columns()
+}
+
+/// Reads width set by shell in `COLUMNS`.
+fn columns() -> Option<usize> {
+    env::var("COLUMNS").ok()?.parse().ok().filter(|&width| width > 0)
+}
+
+/// Checks that standard output is a terminal showing colours, honouring `NO_COLOR`.
+fn color_terminal() -> bool {
+    if env::var_os("NO_COLOR").is_some() || env::var("TERM").is_ok_and(|term| term == "dumb") {
+        return false;
+    }
+    io::stdout().is_terminal()
+}
+
+/// Observer printing progress of solver as documented for `--verbose` levels:
+///
+/// 1 - result graph (printed by caller, after solving)
+/// 2 - text after every temperature step
+/// 3 - graph after every temperature step
+/// 4 - text after every iteration
+/// 5 - graph after every iteration
+pub struct Printer {
+    verbosity: u8,
+    gantt: TerminalGantt,
+}
+
+impl Printer {
+    pub fn new(verbosity: u8) -> Self {
+        Self {
+            verbosity,
+            gantt: TerminalGantt::detect(),
+        }
+    }
+
+    /// Sets how graphs are drawn.
+    pub fn with_gantt(mut self, gantt: TerminalGantt) -> Self {
+        self.gantt = gantt;
+        self
+    }
+
+    fn show(&self, progress: &Progress<'_>, graph: bool) {
+        println!(
+            "Iteration {}: temperature {:.4}, makespan {}, best {}",
+            progress.iteration, progress.temperature, progress.makespan, progress.best
+        );
+        if let (true, Some(solution)) = (graph, progress.solution) {
+            print!("{}", self.gantt.render(&solution.to_schedule()));
+        }
+    }
+}
+
+impl Observer for Printer {
+    fn event(&mut self, event: Event, progress: &Progress<'_>) {
+        match event {
+            Event::Moved { .. } if self.verbosity >= 4 => self.show(progress, self.verbosity >= 5),
+            Event::TemperatureChanged { .. } if (2..=3).contains(&self.verbosity) => {
+                self.show(progress, self.verbosity == 3)
             }
-            print!("\n");
+            Event::Restarted { restarts } if self.verbosity >= 2 => println!("Restart {}", restarts),
+            _ => {}
         }
     }
+
+    /// Solutions are asked for only by events after which graph is drawn.
+    fn interest(&self, kind: EventKind) -> Interest {
+        match kind {
+            EventKind::Moved if self.verbosity >= 5 => Interest::Solution,
+            EventKind::Moved if self.verbosity == 4 => Interest::Event,
+            EventKind::TemperatureChanged if self.verbosity == 3 => Interest::Solution,
+            EventKind::TemperatureChanged if self.verbosity == 2 => Interest::Event,
+            EventKind::Restarted if self.verbosity >= 2 => Interest::Event,
+            _ => Interest::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_pretty_print {
+    use super::*;
+    use crate::utils::{Core, Task};
+
+    fn schedule() -> Schedule {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from(vec![Task::with_length(40), Task::with_length(60)]));
+        schedule.add_core(Core::from(vec![Task::with_length(50)]));
+        schedule
+    }
+
+    #[test]
+    fn test_rows_scaled_to_makespan() {
+        let gantt = TerminalGantt::detect().with_color(false).with_width(60);
+        let chart = gantt.render(&schedule());
+        let rows: Vec<&str> = chart.lines().collect();
+
+        // Bar takes what's left of 60 columns after core labels, loads and frame.
+        assert_eq!(rows[0], format!("Core 1 |{}{}| load 100, idle   0", "#".repeat(13), "=".repeat(19)));
+        assert_eq!(rows[1], format!("Core 2 |{}{}| load  50, idle  50", "#".repeat(16), " ".repeat(16)));
+        assert!(rows.iter().all(|row| row.chars().count() == 60));
+    }
+
+    #[test]
+    fn test_labels() {
+        let gantt = TerminalGantt::detect().with_color(false).with_width(60);
+
+        let chart = gantt.with_labels(TaskLabels::Ids).render(&schedule());
+        assert!(chart.contains("|#####t1######========t2=========|"));
+        assert!(chart.contains("|#######t3#######"));
+
+        let chart = gantt.with_labels(TaskLabels::Lengths).render(&schedule());
+        assert!(chart.contains("#####40######========60========="));
+    }
+
+    #[test]
+    fn test_color() {
+        let chart = TerminalGantt::detect().with_color(true).with_width(60).render(&schedule());
+        assert!(chart.contains('\u{1b}'));
+        assert!(chart.contains('│'));
+        assert!(!chart.contains('#'));
+    }
+
+    #[test]
+    fn test_printer_needs_solutions_for_graphs() {
+        use Interest::{Event as E, Ignored as I, Solution as S};
+        let interests = |kind| -> Vec<Interest> {
+            (0..=5).map(|verbosity| Printer::new(verbosity).interest(kind)).collect()
+        };
+
+        assert_eq!(interests(EventKind::TemperatureChanged), vec![I, I, E, S, I, I]);
+        assert_eq!(interests(EventKind::Moved), vec![I, I, I, I, E, S]);
+        assert_eq!(interests(EventKind::Restarted), vec![I, I, E, E, E, E]);
+        assert_eq!(interests(EventKind::Started), vec![I; 6]);
+    }
 }
@@ -1,12 +1,55 @@
 // #![allow(dead_code)]
-use crate::utils::{Case, Core, Schedule};
-use rand::Rng;
+use crate::solver::{Budget, Observer, Outcome, Solver, SolverStats};
+use crate::utils::{Case, Core, Schedule, Settings};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::time::Instant;
 
-pub fn schedule(case: &Case) -> Schedule {
+/// Assigns tasks to random cores.
+pub struct Random {
+    seed: u64,
+}
+
+impl Random {
+    /// Creates solver drawing cores from generator seeded with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl Solver for Random {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn solve(&self, case: &Case, _: Option<Schedule>, _: &Budget, observer: &mut dyn Observer) -> Outcome {
+        let timer = Instant::now();
+        let schedule = schedule(case, &mut ChaCha12Rng::seed_from_u64(self.seed));
+
+        let makespan = schedule.makespan().unwrap();
+        observer.record(0, makespan);
+        observer.finish();
+
+        Outcome {
+            schedule,
+            stats: SolverStats {
+                elapsed: timer.elapsed(),
+                optimal: makespan <= case.lower_bound(),
+                ..SolverStats::default()
+            },
+        }
+    }
+}
+
+pub fn solver(settings: &Settings) -> Result<Box<dyn Solver>, String> {
+    Ok(Box::new(Random::new(settings.seed)))
+}
+
+/// Assigns every task of `case` to uniformly chosen core using given random generator.
+pub fn schedule<R: Rng>(case: &Case, rng: &mut R) -> Schedule {
     let mut cpu: Vec<Core> = (0..case.cores()).map(|_| Core::new()).collect();
 
-    let mut rng = rand::thread_rng();
-    let tasks = case.tasks().to_owned();
+    let tasks = case.tasks();
 
     tasks.iter().for_each(|&task| {
         let core_idx = rng.gen_range(0..cpu.len());
@@ -21,3 +64,73 @@ pub fn schedule(case: &Case) -> Schedule {
 
     schedule
 }
+
+/// Returns copy of `schedule` in which `moves` randomly chosen tasks
+/// were moved to randomly chosen cores.
+pub fn perturb<R: Rng>(schedule: &Schedule, moves: usize, rng: &mut R) -> Schedule {
+    let mut timelines: Vec<_> = schedule.cores().iter().map(Core::get_tasks).collect();
+
+    if timelines.len() < 2 {
+        return schedule.clone();
+    }
+
+    for _ in 0..moves {
+        let source = rng.gen_range(0..timelines.len());
+        if timelines[source].is_empty() {
+            continue;
+        }
+        let task = rng.gen_range(0..timelines[source].len());
+        let task = timelines[source].swap_remove(task);
+        let target = rng.gen_range(0..timelines.len());
+        timelines[target].push(task);
+    }
+
+    let mut perturbed = Schedule::new();
+    for timeline in timelines {
+        perturbed.add_core(Core::from(timeline));
+    }
+
+    perturbed
+}
+
+#[cfg(test)]
+mod test_random_schedule {
+    use super::*;
+    use crate::greedy;
+    use crate::utils::Task;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn case() -> Case {
+        let mut case = Case::new().with_cores(3);
+        case.add_tasks((1..10).map(Task::with_length).collect());
+        case
+    }
+
+    #[test]
+    fn test_schedule_keeps_tasks() {
+        let case = case();
+        let schedule = schedule(&case, &mut StdRng::seed_from_u64(3));
+
+        let scheduled: usize = schedule.cores().iter().map(|core| core.get_tasks().len()).sum();
+        assert_eq!(schedule.cores().len(), 3);
+        assert_eq!(scheduled, 9);
+    }
+
+    #[test]
+    fn test_perturb_keeps_tasks() {
+        let case = case();
+        let lpt = greedy::schedule(&case);
+        let perturbed = perturb(&lpt, 5, &mut StdRng::seed_from_u64(7));
+
+        let mut lengths: Vec<u64> = perturbed
+            .cores()
+            .iter()
+            .flat_map(|core| core.get_tasks())
+            .map(|task| task.length())
+            .collect();
+        lengths.sort_unstable();
+
+        assert_eq!(perturbed.cores().len(), 3);
+        assert_eq!(lengths, (1..10).collect::<Vec<u64>>());
+    }
+}
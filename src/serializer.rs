@@ -1,4 +1,44 @@
-use std::{error::Error, io::Write};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    io::Write,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Format of log file.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum LogFormat {
+    /// `iteration,makespan` rows, runs separated by `---`, as read by `dataplot.py`.
+    #[default]
+    Csv,
+    /// JSON object per line, every run starting with header record.
+    Jsonl,
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Csv => write!(f, "csv"),
+            Self::Jsonl => write!(f, "jsonl"),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "csv" => Ok(Self::Csv),
+            "jsonl" => Ok(Self::Jsonl),
+            _ => Err(format!("Unknown log format `{}`.", name)),
+        }
+    }
+}
+
 pub struct Serializer<T: Write> {
     is_buffered: bool,
     records: Vec<Record>,
@@ -10,7 +50,7 @@ impl<T: Write> Serializer<T> {
         Self {
             is_buffered: true,
             records: Vec::new(),
-            writer: writer,
+            writer,
         }
     }
 
@@ -27,6 +67,14 @@ impl<T: Write> Serializer<T> {
         self
     }
 
+    /// Writes `comment` line (prefixed with `#`) after all records added so far.
+    pub fn add_comment(&mut self, comment: &str) -> Result<(), Box<dyn Error>> {
+        self.save("")?;
+        self.writer.write_all(format!("# {}\n", comment).as_bytes())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
     pub fn save(&mut self, delimiter: &str) -> Result<(), Box<dyn Error>> {
         for record in &self.records {
             self.writer
@@ -43,6 +91,7 @@ impl<T: Write> Serializer<T> {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub struct Record {
     iteration: u64,
     makespan: u128,
@@ -56,11 +105,155 @@ impl Record {
         }
     }
 
+    pub fn iteration(&self) -> u64 {
+        self.iteration
+    }
+
+    pub fn makespan(&self) -> u128 {
+        self.makespan
+    }
+
     pub fn serialize(&self) -> String {
         format!("{},{}", self.iteration, self.makespan)
     }
 }
 
+/// Describes run logged in JSON Lines, so its records can be told apart from other runs.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Header {
+    /// Path of case file.
+    pub instance: String,
+    /// Hash of case file contents, see [`fingerprint`].
+    pub hash: String,
+    pub solver: String,
+    pub parameters: BTreeMap<String, String>,
+    pub seed: u64,
+    pub version: String,
+}
+
+/// State of solver after single iteration, logged in JSON Lines.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct JsonRecord {
+    /// Seconds since Unix epoch.
+    pub timestamp: f64,
+    pub iteration: u64,
+    /// Temperature of annealing, absent for other solvers.
+    pub temperature: Option<f64>,
+    pub makespan: u128,
+    pub best: u128,
+    /// Whether proposed move was accepted, absent for other solvers.
+    pub accepted: Option<bool>,
+}
+
+impl JsonRecord {
+    pub fn new(iteration: u64, makespan: u128, best: u128) -> Self {
+        Self::at(SystemTime::now(), iteration, makespan, best)
+    }
+
+    /// Creates record made at `time`.
+    pub fn at(time: SystemTime, iteration: u64, makespan: u128, best: u128) -> Self {
+        Self {
+            timestamp: time.duration_since(UNIX_EPOCH).map_or(0.0, |time| time.as_secs_f64()),
+            iteration,
+            temperature: None,
+            makespan,
+            best,
+            accepted: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Line<'a> {
+    Header(&'a Header),
+    Record(&'a JsonRecord),
+    Comment { text: &'a str },
+    End,
+}
+
+/// Writes log as JSON Lines, buffered like [`Serializer`].
+pub struct JsonLines<T: Write> {
+    is_buffered: bool,
+    lines: Vec<String>,
+    writer: T,
+    /// Best makespan of current run.
+    pub(crate) best: Option<u128>,
+    /// Temperature and acceptance of last move, completing next record.
+    pub(crate) last_move: Option<(f64, bool)>,
+}
+
+impl<T: Write> JsonLines<T> {
+    pub fn new(writer: T) -> Self {
+        Self {
+            is_buffered: true,
+            lines: Vec::new(),
+            writer,
+            best: None,
+            last_move: None,
+        }
+    }
+
+    pub fn buffered(&mut self, is_buffered: bool) -> &Self {
+        self.is_buffered = is_buffered;
+        self
+    }
+
+    /// Starts new run, written right away.
+    pub fn add_header(&mut self, header: &Header) -> Result<(), Box<dyn Error>> {
+        self.best = None;
+        self.add(&Line::Header(header))?;
+        self.save()
+    }
+
+    pub fn add_record(&mut self, record: &JsonRecord) -> Result<(), Box<dyn Error>> {
+        self.add(&Line::Record(record))?;
+        if !self.is_buffered {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn add_comment(&mut self, comment: &str) -> Result<(), Box<dyn Error>> {
+        self.add(&Line::Comment { text: comment })?;
+        self.save()
+    }
+
+    /// Ends current run.
+    pub fn add_end(&mut self) -> Result<(), Box<dyn Error>> {
+        self.best = None;
+        self.last_move = None;
+        self.add(&Line::End)?;
+        self.save()
+    }
+
+    fn add(&mut self, line: &Line<'_>) -> Result<(), Box<dyn Error>> {
+        self.lines.push(serde_json::to_string(line)?);
+        Ok(())
+    }
+
+    pub fn save(&mut self) -> Result<(), Box<dyn Error>> {
+        for line in &self.lines {
+            self.writer.write_all(format!("{}\n", line).as_bytes())?;
+        }
+        self.writer.flush()?;
+        self.lines.clear();
+        Ok(())
+    }
+
+    pub fn get_writer(&self) -> &T {
+        &self.writer
+    }
+}
+
+/// Returns FNV-1a hash of `bytes` as hexadecimal string, stable across platforms and versions.
+pub fn fingerprint(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
 #[cfg(test)]
 mod test_serializer {
     use super::*;
@@ -69,7 +262,7 @@ mod test_serializer {
     fn test_create_empty() {
         let serializer = Serializer::new(Vec::new());
 
-        assert_eq!(serializer.is_buffered, true);
+        assert!(serializer.is_buffered);
         assert!(serializer.records.is_empty());
     }
 
@@ -88,4 +281,61 @@ mod test_serializer {
         let out = String::from_utf8(serializer.get_writer().to_owned()).unwrap();
         assert_eq!(out, String::from("0,0\n1,1\n2,2\n3,3\n4,4\n"));
     }
+
+    #[test]
+    fn test_serialize_comment() {
+        let mut serializer = Serializer::new(Vec::new());
+
+        serializer.add_record(Record::new(0, 7));
+        serializer.add_comment("temperature 5").unwrap();
+        serializer.add_record(Record::new(1, 6));
+        serializer.save("---\n").unwrap();
+
+        let out = String::from_utf8(serializer.get_writer().to_owned()).unwrap();
+        assert_eq!(out, String::from("0,7\n# temperature 5\n1,6\n---\n"));
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let mut log = JsonLines::new(Vec::new());
+        let header = Header {
+            instance: "cases/t.case".to_string(),
+            hash: fingerprint(b"2\n1\n5"),
+            solver: "sa".to_string(),
+            parameters: vec![("runs".to_string(), "1".to_string())].into_iter().collect(),
+            seed: 7,
+            version: "0.1.0".to_string(),
+        };
+        let record = JsonRecord {
+            temperature: Some(2.5),
+            accepted: Some(true),
+            ..JsonRecord::new(1, 9, 8)
+        };
+
+        log.add_header(&header).unwrap();
+        log.add_record(&record).unwrap();
+        log.add_comment("restart").unwrap();
+        log.add_end().unwrap();
+
+        let out = String::from_utf8(log.get_writer().to_owned()).unwrap();
+        let lines: Vec<serde_json::Value> = out.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["type"], "header");
+        assert_eq!(lines[0]["instance"], "cases/t.case");
+        assert_eq!(lines[0]["parameters"]["runs"], "1");
+        assert_eq!(lines[1]["type"], "record");
+        assert_eq!(lines[1]["makespan"], 9);
+        assert_eq!(lines[1]["best"], 8);
+        assert_eq!(lines[1]["temperature"], 2.5);
+        assert_eq!(lines[1]["accepted"], true);
+        assert_eq!(lines[2]["text"], "restart");
+        assert_eq!(lines[3]["type"], "end");
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint(b""), "cbf29ce484222325");
+        assert_eq!(fingerprint(b"a"), "af63dc4c8601ec8c");
+        assert_ne!(fingerprint(b"2\n1\n5"), fingerprint(b"2\n1\n6"));
+    }
 }
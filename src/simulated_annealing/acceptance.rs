@@ -0,0 +1,184 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Rule deciding whether candidate solution replaces current one.
+/// Improving (and equal) candidates are always accepted.\
+/// Metropolis: worsening by Δ is accepted with probability `exp(-Δ / T)`\
+/// Threshold: worsening by Δ is accepted when `Δ < T`\
+/// Great Deluge(r): candidate is accepted when its makespan doesn't exceed water level,
+/// which starts at `makespan + T0` and falls by fraction r of its distance to best makespan every iteration\
+/// Record-to-Record Travel(d): candidate is accepted when its makespan is at most `(1 + d)` times best one\
+/// Late Acceptance(L): candidate is accepted when it isn't worse than current solution from L iterations ago
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Acceptance {
+    Metropolis,
+    Threshold,
+    GreatDeluge(f64),
+    RecordToRecord(f64),
+    Late(usize),
+}
+
+/// State of acceptance rule during single run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct Acceptor {
+    rule: Acceptance,
+    /// Water level of great deluge.
+    level: f64,
+    /// Recent makespans of current solution, used by late acceptance.
+    history: Vec<u128>,
+    iteration: usize,
+}
+
+impl Acceptor {
+    /// Starts `rule` from solution of `makespan` at `temperature`.
+    pub fn new(rule: Acceptance, makespan: u128, temperature: f64) -> Self {
+        let history = match rule {
+            Acceptance::Late(length) => vec![makespan; length.max(1)],
+            _ => Vec::new(),
+        };
+        Self {
+            rule,
+            level: makespan as f64 + temperature,
+            history,
+            iteration: 0,
+        }
+    }
+
+    /// Returns `true` if candidate of makespan `candidate` replaces current solution of makespan `current`.
+    pub fn accept<R: Rng>(&mut self, current: u128, candidate: u128, best: u128, temperature: f64, rng: &mut R) -> bool {
+        let delta = candidate as f64 - current as f64;
+        let accepted = delta <= 0.0
+            || match self.rule {
+                Acceptance::Metropolis => rng.gen::<f64>() < (-delta / temperature).exp(),
+                Acceptance::Threshold => delta < temperature,
+                Acceptance::GreatDeluge(_) => candidate as f64 <= self.level,
+                Acceptance::RecordToRecord(deviation) => candidate as f64 <= best as f64 * (1.0 + deviation),
+                Acceptance::Late(_) => candidate <= self.history[self.iteration % self.history.len()],
+            };
+
+        match self.rule {
+            Acceptance::GreatDeluge(rain) => self.level -= rain * (self.level - best as f64).max(0.0),
+            Acceptance::Late(_) => {
+                let slot = self.iteration % self.history.len();
+                self.history[slot] = if accepted { candidate } else { current };
+            }
+            _ => {}
+        }
+        self.iteration += 1;
+
+        accepted
+    }
+}
+
+impl fmt::Display for Acceptance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Metropolis => write!(f, "metropolis"),
+            Self::Threshold => write!(f, "threshold"),
+            Self::GreatDeluge(rain) => write!(f, "deluge:{}", rain),
+            Self::RecordToRecord(deviation) => write!(f, "record:{}", deviation),
+            Self::Late(length) => write!(f, "late:{}", length),
+        }
+    }
+}
+
+/// Parses `metropolis`, `threshold`, `deluge:RAIN`, `record:DEVIATION` or `late:LENGTH`, as written by `Display`.
+impl FromStr for Acceptance {
+    type Err = String;
+
+    fn from_str(serialized: &str) -> Result<Self, Self::Err> {
+        let invalid = |parameter: &str| format!("Invalid acceptance parameter `{}`.", parameter);
+
+        match serialized.split_once(':') {
+            Some(("deluge", rain)) => rain
+                .parse::<f64>()
+                .ok()
+                .filter(|rain| *rain > 0.0 && *rain <= 1.0)
+                .map(Self::GreatDeluge)
+                .ok_or_else(|| invalid(rain)),
+            Some(("record", deviation)) => deviation
+                .parse::<f64>()
+                .ok()
+                .filter(|deviation| *deviation >= 0.0)
+                .map(Self::RecordToRecord)
+                .ok_or_else(|| invalid(deviation)),
+            Some(("late", length)) => length
+                .parse::<usize>()
+                .ok()
+                .filter(|&length| length > 0)
+                .map(Self::Late)
+                .ok_or_else(|| invalid(length)),
+            Some(_) => Err(format!("Unknown acceptance criterion `{}`.", serialized)),
+            None => match serialized {
+                "metropolis" => Ok(Self::Metropolis),
+                "threshold" => Ok(Self::Threshold),
+                "deluge" | "record" | "late" => Err(format!(
+                    "Acceptance criterion `{}` requires parameter, e.g. `{}:0.01`.",
+                    serialized, serialized
+                )),
+                _ => Err(format!("Unknown acceptance criterion `{}`.", serialized)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_acceptance {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_acceptance_from_str() {
+        for acceptance in &[
+            Acceptance::Metropolis,
+            Acceptance::Threshold,
+            Acceptance::GreatDeluge(0.001),
+            Acceptance::RecordToRecord(0.02),
+            Acceptance::Late(50),
+        ] {
+            assert_eq!(acceptance.to_string().parse::<Acceptance>().as_ref(), Ok(acceptance));
+        }
+        assert!("late".parse::<Acceptance>().is_err());
+        assert!("late:0".parse::<Acceptance>().is_err());
+        assert!("deluge:2".parse::<Acceptance>().is_err());
+        assert!("tabu".parse::<Acceptance>().is_err());
+    }
+
+    #[test]
+    fn test_threshold_and_record() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut threshold = Acceptor::new(Acceptance::Threshold, 100, 5.0);
+        let mut record = Acceptor::new(Acceptance::RecordToRecord(0.1), 100, 5.0);
+
+        assert!(threshold.accept(100, 104, 100, 5.0, &mut rng));
+        assert!(!threshold.accept(100, 105, 100, 5.0, &mut rng));
+        assert!(record.accept(105, 110, 100, 5.0, &mut rng));
+        assert!(!record.accept(105, 111, 100, 5.0, &mut rng));
+    }
+
+    #[test]
+    fn test_great_deluge_level_falls() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut deluge = Acceptor::new(Acceptance::GreatDeluge(0.5), 100, 8.0);
+
+        assert!(deluge.accept(100, 108, 100, 8.0, &mut rng));
+        // Level fell from 108 to 104.
+        assert!(!deluge.accept(100, 105, 100, 8.0, &mut rng));
+        assert!(deluge.accept(100, 101, 100, 8.0, &mut rng));
+        assert!(deluge.accept(100, 99, 100, 8.0, &mut rng));
+    }
+
+    #[test]
+    fn test_late_acceptance() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut late = Acceptor::new(Acceptance::Late(2), 100, 1.0);
+
+        assert!(!late.accept(90, 101, 90, 1.0, &mut rng));
+        assert!(late.accept(90, 100, 90, 1.0, &mut rng));
+        // History now remembers 90 and 100.
+        assert!(!late.accept(100, 101, 90, 1.0, &mut rng));
+        assert!(late.accept(100, 100, 90, 1.0, &mut rng));
+        assert_eq!(late.history, vec![100, 100]);
+    }
+}
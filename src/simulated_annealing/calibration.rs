@@ -0,0 +1,169 @@
+use super::Neighbourhood;
+use crate::utils::Assignment;
+use rand::Rng;
+use std::{fmt, str::FromStr};
+
+/// Number of moves sampled when calibrating temperatures.
+pub const DEFAULT_SAMPLES: usize = 1000;
+
+/// Target acceptance probabilities of worsening moves, from which temperatures are calibrated.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Calibration {
+    /// Probability of accepting average worsening move at initial temperature.
+    pub initial_acceptance: f64,
+    /// Probability of accepting average worsening move at final temperature.
+    /// Final temperature isn't calibrated when `None`.
+    pub final_acceptance: Option<f64>,
+    /// Number of sampled moves.
+    pub samples: usize,
+}
+
+impl Calibration {
+    /// Creates calibration of initial temperature, `initial_acceptance` has to be in (0, 1).
+    pub fn new(initial_acceptance: f64) -> Result<Self, String> {
+        if !(initial_acceptance > 0.0 && initial_acceptance < 1.0) {
+            return Err(format!("Acceptance probability {} isn't in (0, 1).", initial_acceptance));
+        }
+        Ok(Self {
+            initial_acceptance,
+            final_acceptance: None,
+            samples: DEFAULT_SAMPLES,
+        })
+    }
+
+    /// Calibrates final temperature as well. It has to be lower than initial one,
+    /// so `final_acceptance` has to be in (0, initial acceptance).
+    pub fn with_final_acceptance(mut self, final_acceptance: f64) -> Result<Self, String> {
+        if !(final_acceptance > 0.0 && final_acceptance < self.initial_acceptance) {
+            return Err(format!(
+                "Final acceptance probability {} isn't in (0, {}), it has to be below initial one.",
+                final_acceptance, self.initial_acceptance
+            ));
+        }
+        self.final_acceptance = Some(final_acceptance);
+        Ok(self)
+    }
+
+    /// Samples neighbours of `solution` proposed by `neighbourhood` and returns initial temperature
+    /// (and final one if requested) at which Metropolis rule accepts average worsening move
+    /// with target probability, i.e. `T = -mean(Δ) / ln(p)`.
+    ///
+    /// Returns `None` when no sampled move makes solution worse.
+    pub fn calibrate<R: Rng>(
+        &self,
+        solution: &Assignment,
+        neighbourhood: &Neighbourhood,
+        rng: &mut R,
+    ) -> Option<(f64, Option<f64>)> {
+        let makespan = solution.makespan();
+        let mut sampled = solution.clone();
+        let mut moves = Vec::new();
+        let mut worsening = Vec::new();
+
+        for _ in 0..self.samples {
+            moves.clear();
+            neighbourhood.propose(&sampled, rng, &mut moves);
+            for &mv in &moves {
+                sampled.apply(mv);
+            }
+            let candidate = sampled.makespan();
+            for &mv in moves.iter().rev() {
+                sampled.undo(mv);
+            }
+            if candidate > makespan {
+                worsening.push((candidate - makespan) as f64);
+            }
+        }
+
+        if worsening.is_empty() {
+            return None;
+        }
+
+        let mean = worsening.iter().sum::<f64>() / worsening.len() as f64;
+        let temperature = |acceptance: f64| -mean / acceptance.ln();
+
+        Some((
+            temperature(self.initial_acceptance),
+            self.final_acceptance.map(temperature),
+        ))
+    }
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.final_acceptance {
+            Some(last) => write!(f, "{},{}", self.initial_acceptance, last),
+            None => write!(f, "{}", self.initial_acceptance),
+        }
+    }
+}
+
+/// Reads `INITIAL[,FINAL]` acceptance probabilities.
+impl FromStr for Calibration {
+    type Err = String;
+
+    fn from_str(serialized: &str) -> Result<Self, Self::Err> {
+        let probability = |value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("Acceptance probability `{}` isn't a number.", value.trim()))
+        };
+        match serialized.split(',').collect::<Vec<_>>()[..] {
+            [initial] => Self::new(probability(initial)?),
+            [initial, last] => Self::new(probability(initial)?)?.with_final_acceptance(probability(last)?),
+            _ => Err("Calibration takes initial and optionally final acceptance probability.".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_calibration {
+    use super::*;
+    use crate::utils::{Core, Schedule, Task};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_calibrate() {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from(vec![Task::with_length(10), Task::with_length(10)]));
+        schedule.add_core(Core::from(vec![Task::with_length(10), Task::with_length(10)]));
+        let solution = Assignment::from(&schedule);
+
+        // Every move makes makespan worse by exactly 10.
+        let (initial, last) = Calibration::new(0.5)
+            .and_then(|calibration| calibration.with_final_acceptance(0.01))
+            .unwrap()
+            .calibrate(&solution, &Neighbourhood::default(), &mut StdRng::seed_from_u64(1))
+            .unwrap();
+
+        assert!((initial - 10.0 / 2f64.ln()).abs() < 1e-9);
+        assert!((last.unwrap() - 10.0 / 100f64.ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_calibrate_without_worsening_moves() {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from(vec![Task::with_length(10)]));
+        schedule.add_core(Core::new());
+        let solution = Assignment::from(&schedule);
+
+        let calibrated = Calibration::new(0.5).unwrap().calibrate(&solution, &Neighbourhood::default(), &mut StdRng::seed_from_u64(1));
+
+        assert_eq!(calibrated, None);
+    }
+
+    #[test]
+    fn test_calibration_from_str() {
+        let calibration: Calibration = "0.8, 0.001".parse().unwrap();
+
+        assert_eq!(calibration.initial_acceptance, 0.8);
+        assert_eq!(calibration.final_acceptance, Some(0.001));
+        assert_eq!(calibration.to_string().parse(), Ok(calibration));
+        assert!("abc".parse::<Calibration>().is_err());
+        assert!("1".parse::<Calibration>().is_err());
+        assert!("0".parse::<Calibration>().is_err());
+        assert!("0.2,0.9".parse::<Calibration>().is_err());
+        assert!("0.8,0.5,0.1".parse::<Calibration>().is_err());
+    }
+}
@@ -0,0 +1,251 @@
+use super::{acceptance::Acceptor, SimulatedAnnealingParams};
+use crate::utils::{Assignment, Case};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// State of random generator, enough to continue its sequence exactly.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub(super) struct GeneratorState {
+    seed: [u8; 32],
+    stream: u64,
+    word_pos: u128,
+}
+
+impl GeneratorState {
+    pub fn restore(&self) -> ChaCha12Rng {
+        let mut rng = ChaCha12Rng::from_seed(self.seed);
+        rng.set_stream(self.stream);
+        rng.set_word_pos(self.word_pos);
+        rng
+    }
+}
+
+impl From<&ChaCha12Rng> for GeneratorState {
+    fn from(rng: &ChaCha12Rng) -> Self {
+        Self {
+            seed: rng.get_seed(),
+            stream: rng.get_stream(),
+            word_pos: rng.get_word_pos(),
+        }
+    }
+}
+
+/// Statistics of temperature step in progress.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub(super) struct PartialStep {
+    /// Iterations made at current temperature so far.
+    pub iterations: u16,
+    pub accepted: u32,
+    /// Sum of makespans observed at current temperature, and sum of their squares.
+    pub sum: f64,
+    pub sum_of_squares: f64,
+}
+
+/// Snapshot of annealing, taken between two temperature steps
+/// or in the middle of one when a limit stops the simulation there.
+///
+/// Run resumed from checkpoint with the same case and parameters
+/// continues exactly like the interrupted one would.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub(super) seed: u64,
+    /// Fingerprint of parameters of interrupted simulation, see [`SimulatedAnnealingParams::fingerprint`].
+    pub(super) parameters: Vec<(String, String)>,
+    pub(super) current: Assignment,
+    pub(super) best: Assignment,
+    pub(super) temperature: f64,
+    /// Initial and final temperature, after calibration.
+    pub(super) temperatures: (f64, f64),
+    pub(super) iteration: u64,
+    pub(super) evaluations: u64,
+    pub(super) changeless_iterations: u16,
+    pub(super) since_improvement: u64,
+    pub(super) restarts: u32,
+    pub(super) step: u64,
+    pub(super) partial_step: PartialStep,
+    pub(super) acceptor: Acceptor,
+    pub(super) generator: GeneratorState,
+    pub(super) wall_time: Duration,
+    pub(super) cpu_time: Duration,
+}
+
+impl Checkpoint {
+    /// Returns seed of interrupted simulation.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns number of iterations made before checkpoint was taken.
+    pub fn iterations(&self) -> u64 {
+        self.iteration - 1
+    }
+
+    /// Returns best makespan found before checkpoint was taken.
+    pub fn best_makespan(&self) -> u128 {
+        self.best.makespan()
+    }
+
+    /// Checks that checkpoint was taken while scheduling `case`.
+    pub fn fits(&self, case: &Case) -> Result<(), String> {
+        self.current
+            .to_schedule()
+            .validate(case)
+            .map_err(|err| format!("Checkpoint was taken while scheduling different case. {}", err))
+    }
+
+    /// Checks that checkpoint was taken by simulation running with the same `params`.
+    pub fn fits_params(&self, params: &SimulatedAnnealingParams) -> Result<(), String> {
+        self.matches(&params.fingerprint())
+    }
+
+    /// Checks that checkpoint was taken by simulation with given parameters `fingerprint`,
+    /// naming parameters which differ.
+    pub(super) fn matches(&self, fingerprint: &[(String, String)]) -> Result<(), String> {
+        let differences: Vec<String> = fingerprint
+            .iter()
+            .filter(|parameter| !self.parameters.contains(parameter))
+            .map(|(name, value)| {
+                let taken = self
+                    .parameters
+                    .iter()
+                    .find(|(taken, _)| taken == name)
+                    .map_or("unknown", |(_, value)| value.as_str());
+                format!("{} {} instead of {}", name, taken, value)
+            })
+            .collect();
+        if differences.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Checkpoint was taken with different parameters: {}.", differences.join(", ")))
+        }
+    }
+
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let source = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&source)?)
+    }
+
+    /// Writes checkpoint to temporary file first and then renames it,
+    /// so interrupted write never corrupts previous checkpoint.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, serde_json::to_string(self)?)?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+}
+
+/// Where and how often running annealing saves checkpoints.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Checkpoints {
+    pub path: PathBuf,
+    /// Minimal wall-clock time between two checkpoints.
+    /// Checkpoint is also saved when simulation stops.
+    pub interval: Duration,
+}
+
+/// Reads checkpoint from `path`, describing failure in a message.
+pub(crate) fn read<P: AsRef<Path>>(path: P) -> Result<Checkpoint, String> {
+    let path = path.as_ref();
+    Checkpoint::read_from_file(path).map_err(|err| format!("Can't read checkpoint `{}`: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod test_checkpoint {
+    use super::*;
+    use crate::utils::{Core, Schedule, Task};
+    use rand::Rng;
+    use std::env;
+
+    fn checkpoint() -> Checkpoint {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from(vec![Task::with_length(4), Task::with_length(2)]));
+        schedule.add_core(Core::from(vec![Task::with_length(3)]));
+        let current = Assignment::from(&schedule);
+        let mut rng = ChaCha12Rng::seed_from_u64(7);
+        rng.gen::<u64>();
+
+        Checkpoint {
+            seed: 7,
+            parameters: SimulatedAnnealingParams::default().fingerprint(),
+            best: current.clone(),
+            temperature: 12.5,
+            temperatures: (50.0, 0.1),
+            iteration: 41,
+            evaluations: 90,
+            changeless_iterations: 3,
+            since_improvement: 5,
+            restarts: 1,
+            step: 2,
+            partial_step: PartialStep::default(),
+            acceptor: Acceptor::new(super::super::Acceptance::Late(3), current.makespan(), 50.0),
+            generator: GeneratorState::from(&rng),
+            wall_time: Duration::from_millis(1500),
+            cpu_time: Duration::from_millis(1200),
+            current,
+        }
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let path = env::temp_dir().join(format!("max-checkpoint-{}.json", std::process::id()));
+        let saved = checkpoint();
+        saved.write_to_file(&path).unwrap();
+        let read = Checkpoint::read_from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(read.iterations(), 40);
+        assert_eq!(read.current.to_schedule(), saved.current.to_schedule());
+        assert_eq!(read.wall_time, saved.wall_time);
+        assert_eq!(read.generator, saved.generator);
+
+        let mut expected = ChaCha12Rng::seed_from_u64(7);
+        expected.gen::<u64>();
+        assert_eq!(read.generator.restore().gen::<u64>(), expected.gen::<u64>());
+    }
+
+    #[test]
+    fn test_fits_case() {
+        let checkpoint = checkpoint();
+        let mut case = Case::new().with_cores(2);
+        case.add_tasks(vec![Task::with_length(3), Task::with_length(2), Task::with_length(4)]);
+        assert!(checkpoint.fits(&case).is_ok());
+
+        case.add_task(Task::with_length(1));
+        assert!(checkpoint.fits(&case).is_err());
+        assert!(checkpoint.fits(&Case::new().with_cores(3)).is_err());
+    }
+
+    #[test]
+    fn test_fits_params() {
+        let checkpoint = checkpoint();
+        let params = SimulatedAnnealingParams::default();
+        assert!(checkpoint.fits_params(&params).is_ok());
+        // Seed and time limit may differ.
+        let params = SimulatedAnnealingParams {
+            seed: 9,
+            max_simulation_time: 5,
+            ..params
+        };
+        assert!(checkpoint.fits_params(&params).is_ok());
+
+        let err = checkpoint
+            .fits_params(&SimulatedAnnealingParams {
+                iterations_per_temperature: 7,
+                acceptance: super::super::Acceptance::Threshold,
+                ..params
+            })
+            .unwrap_err();
+        assert!(err.contains("acceptance metropolis instead of threshold"), "{}", err);
+        assert!(err.contains("iterations per temperature 20 instead of 7"), "{}", err);
+    }
+}
@@ -0,0 +1,214 @@
+use std::{fmt, str::FromStr};
+
+/// Temperature reduction rule used by evaluation algorithm.\
+/// Linear(α): `T = T - α`, in that one α have to be positive\
+/// Geometric(α): `T = T * α`, in that one α have to be from range (0;1)\
+/// Slow Decrease(β): `T = T / (1 + βT)`\
+/// Logarithmic(c): `T = T0 / (1 + c ln(1 + k))`, where k is number of temperature steps made\
+/// Exponential Time: `T = T0 (Tf / T0)^t`, where t is elapsed fraction of time limit\
+/// Lam(α): `T = T * α` when acceptance ratio is above target of modified Lam schedule,
+/// `T = T / α` otherwise, in that one α have to be from range (0;1)\
+/// Huang(λ): `T = T exp(-λT / σ)`, where σ is standard deviation of makespans at temperature T
+#[derive(PartialEq, Debug, Clone)]
+pub enum Reduction {
+    Linear(f64),
+    Geometric(f64),
+    SlowDecrease(f64),
+    Logarithmic(f64),
+    ExponentialTime,
+    Lam(f64),
+    Huang(f64),
+}
+
+/// What happened during single temperature step, used by time-based and adaptive rules.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct CoolingStep {
+    /// Number of temperature steps made so far, including this one.
+    pub step: u64,
+    pub initial_temperature: f64,
+    pub final_temperature: f64,
+    /// Elapsed fraction of time limit.
+    pub elapsed: f64,
+    /// Fraction of moves accepted at this temperature.
+    pub acceptance: f64,
+    /// Standard deviation of makespans observed at this temperature.
+    pub deviation: f64,
+}
+
+impl Reduction {
+    /// Returns temperature following `temperature` after `step`.
+    pub fn reduce(&self, temperature: f64, step: &CoolingStep) -> f64 {
+        match *self {
+            Reduction::Linear(alpha) => temperature - alpha,
+            Reduction::Geometric(alpha) => temperature * alpha,
+            Reduction::SlowDecrease(beta) => temperature / (1.0 + beta * temperature),
+            Reduction::Logarithmic(c) => step.initial_temperature / (1.0 + c * (1.0 + step.step as f64).ln()),
+            Reduction::ExponentialTime => {
+                let ratio = step.final_temperature / step.initial_temperature;
+                step.initial_temperature * ratio.powf(step.elapsed.min(1.0))
+            }
+            Reduction::Lam(alpha) => {
+                if step.acceptance > lam_target(step.elapsed) {
+                    temperature * alpha
+                } else {
+                    temperature / alpha
+                }
+            }
+            Reduction::Huang(lambda) => {
+                // Frozen step (no variance) would drop temperature to zero at once,
+                // so single step can at most halve it.
+                let factor = if step.deviation > 0.0 {
+                    (-lambda * temperature / step.deviation).exp()
+                } else {
+                    0.0
+                };
+                temperature * factor.max(0.5)
+            }
+        }
+    }
+}
+
+/// Target acceptance ratio of modified Lam schedule after `elapsed` fraction of the run:
+/// falls exponentially from 1 to 0.44 during first 15%, stays at 0.44 until 65%
+/// and then falls exponentially towards 0.
+fn lam_target(elapsed: f64) -> f64 {
+    if elapsed < 0.15 {
+        0.44 + 0.56 * 560f64.powf(-elapsed / 0.15)
+    } else if elapsed < 0.65 {
+        0.44
+    } else {
+        0.44 * 440f64.powf(-(elapsed - 0.65) / 0.35)
+    }
+}
+
+impl fmt::Display for Reduction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Linear(alpha) => write!(f, "linear:{}", alpha),
+            Self::Geometric(alpha) => write!(f, "geometric:{}", alpha),
+            Self::SlowDecrease(beta) => write!(f, "slow:{}", beta),
+            Self::Logarithmic(c) => write!(f, "logarithmic:{}", c),
+            Self::ExponentialTime => write!(f, "exponential-time"),
+            Self::Lam(alpha) => write!(f, "lam:{}", alpha),
+            Self::Huang(lambda) => write!(f, "huang:{}", lambda),
+        }
+    }
+}
+
+/// Parses `NAME:PARAMETER` (or just `exponential-time`), as written by `Display`.
+impl FromStr for Reduction {
+    type Err = String;
+
+    fn from_str(serialized: &str) -> Result<Self, Self::Err> {
+        if serialized == "exponential-time" {
+            return Ok(Self::ExponentialTime);
+        }
+
+        let (name, parameter) = serialized
+            .split_once(':')
+            .ok_or_else(|| format!("Cooling schedule `{}` requires parameter, e.g. `{}:0.9`.", serialized, serialized))?;
+        let parameter = parameter
+            .parse::<f64>()
+            .map_err(|_| format!("Invalid cooling parameter `{}`.", parameter))?;
+
+        let reduction = match name {
+            "linear" => Self::Linear(parameter),
+            "geometric" => Self::Geometric(parameter),
+            "slow" => Self::SlowDecrease(parameter),
+            "logarithmic" => Self::Logarithmic(parameter),
+            "lam" => Self::Lam(parameter),
+            "huang" => Self::Huang(parameter),
+            _ => return Err(format!("Unknown cooling schedule `{}`.", name)),
+        };
+        match reduction {
+            Self::Geometric(_) | Self::Lam(_) if !(parameter > 0.0 && parameter < 1.0) => Err(format!(
+                "Invalid cooling parameter `{}`, it has to be from range (0;1).",
+                parameter
+            )),
+            _ if !(parameter > 0.0 && parameter.is_finite()) => Err(format!(
+                "Invalid cooling parameter `{}`, it has to be positive.",
+                parameter
+            )),
+            _ => Ok(reduction),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cooling {
+    use super::*;
+
+    fn step() -> CoolingStep {
+        CoolingStep {
+            step: 1,
+            initial_temperature: 100.0,
+            final_temperature: 1.0,
+            ..CoolingStep::default()
+        }
+    }
+
+    #[test]
+    fn test_reduction_from_str() {
+        for reduction in &[
+            Reduction::Linear(0.5),
+            Reduction::Geometric(0.995),
+            Reduction::SlowDecrease(0.01),
+            Reduction::Logarithmic(2.0),
+            Reduction::ExponentialTime,
+            Reduction::Lam(0.999),
+            Reduction::Huang(0.7),
+        ] {
+            assert_eq!(reduction.to_string().parse::<Reduction>().as_ref(), Ok(reduction));
+        }
+        assert!("geometric".parse::<Reduction>().is_err());
+        assert!("quadratic:2".parse::<Reduction>().is_err());
+        for invalid in &[
+            "geometric:0",
+            "geometric:1",
+            "geometric:1.5",
+            "lam:-0.5",
+            "lam:1",
+            "linear:0",
+            "slow:-0.01",
+            "logarithmic:0",
+            "huang:-1",
+            "huang:NaN",
+        ] {
+            assert!(invalid.parse::<Reduction>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_exponential_time() {
+        let halfway = CoolingStep { elapsed: 0.5, ..step() };
+        let over = CoolingStep { elapsed: 1.5, ..step() };
+
+        assert!((Reduction::ExponentialTime.reduce(100.0, &halfway) - 10.0).abs() < 1e-9);
+        assert!((Reduction::ExponentialTime.reduce(100.0, &over) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_logarithmic() {
+        let step = CoolingStep { step: 3, ..step() };
+        let expected = 100.0 / (1.0 + 2.0 * 4f64.ln());
+        assert!((Reduction::Logarithmic(2.0).reduce(50.0, &step) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lam_follows_target_acceptance() {
+        let accepting = CoolingStep { elapsed: 0.3, acceptance: 0.9, ..step() };
+        let rejecting = CoolingStep { elapsed: 0.3, acceptance: 0.1, ..step() };
+
+        assert!(Reduction::Lam(0.9).reduce(10.0, &accepting) < 10.0);
+        assert!(Reduction::Lam(0.9).reduce(10.0, &rejecting) > 10.0);
+    }
+
+    #[test]
+    fn test_huang() {
+        let varied = CoolingStep { deviation: 100.0, ..step() };
+        let frozen = CoolingStep { deviation: 0.0, ..step() };
+
+        assert!((Reduction::Huang(0.7).reduce(10.0, &varied) - 10.0 * (-0.07f64).exp()).abs() < 1e-9);
+        assert_eq!(Reduction::Huang(0.7).reduce(10.0, &frozen), 5.0);
+    }
+}
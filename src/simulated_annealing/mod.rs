@@ -0,0 +1,877 @@
+mod acceptance;
+mod calibration;
+mod checkpoint;
+mod cooling;
+mod neighbourhood;
+mod restart;
+
+pub use self::acceptance::Acceptance;
+pub use self::calibration::Calibration;
+pub use self::checkpoint::{Checkpoint, Checkpoints};
+pub(crate) use self::checkpoint::read as read_checkpoint;
+pub use self::cooling::{CoolingStep, Reduction};
+pub use self::neighbourhood::Neighbourhood;
+pub use self::restart::{Restart, RestartPolicy};
+
+use self::acceptance::Acceptor;
+use self::checkpoint::{GeneratorState, PartialStep};
+use crate::{
+    multistart::{self, InitialSolution},
+    solver::{Budget, Event, Observer, Outcome, Progress, Solver, SolverStats, Termination, Usage},
+    utils::{Assignment, Case, CpuClock, Incumbent, Move, Schedule, Settings},
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+pub struct SimulatedAnnealingParams {
+    pub(crate) initial_solution: Schedule,
+    pub(crate) initial_temperature: f64,
+    pub(crate) final_temperature: f64,
+    pub(crate) reduction_rule: Reduction,
+    /// Operators proposing neighbouring solutions.
+    pub(crate) neighbourhood: Neighbourhood,
+    /// Rule accepting worse solutions.
+    pub(crate) acceptance: Acceptance,
+    pub(crate) iterations_per_temperature: u16,
+    pub(crate) max_changeless_iterations: u16,
+    pub(crate) max_simulation_time: u16,
+    /// Seed of random generator driving the simulation.
+    pub(crate) seed: u64,
+    /// Calibrates temperatures from initial solution instead of using fixed ones.
+    pub(crate) calibration: Option<Calibration>,
+    /// Restarts stagnating simulation.
+    pub(crate) restart: Option<RestartPolicy>,
+}
+
+impl Default for SimulatedAnnealingParams {
+    fn default() -> Self {
+        Self {
+            initial_solution: Schedule::new(),
+            initial_temperature: 75.0,
+            final_temperature: 0.01,
+            reduction_rule: Reduction::Geometric(0.995),
+            neighbourhood: Neighbourhood::default(),
+            acceptance: Acceptance::Metropolis,
+            iterations_per_temperature: 20,
+            max_simulation_time: 120,
+            max_changeless_iterations: 12000,
+            seed: 0,
+            calibration: None,
+            restart: None,
+        }
+    }
+}
+
+impl SimulatedAnnealingParams {
+    /// Returns named parameters which a checkpoint has to share with simulation resumed from it.
+    /// Seed, time limit and initial solution aren't included, checkpoint carries its own state of those.
+    pub(crate) fn fingerprint(&self) -> Vec<(String, String)> {
+        let restart = self.restart.as_ref().map(|policy| {
+            let max = policy.max_restarts.map_or("unlimited".to_string(), |max| max.to_string());
+            format!("{} after {}, at most {}", policy.strategy, policy.stagnation, max)
+        });
+        vec![
+            ("initial temperature", self.initial_temperature.to_string()),
+            ("final temperature", self.final_temperature.to_string()),
+            ("cooling", self.reduction_rule.to_string()),
+            ("operators", self.neighbourhood.to_string()),
+            ("acceptance", self.acceptance.to_string()),
+            ("iterations per temperature", self.iterations_per_temperature.to_string()),
+            ("max changeless iterations", self.max_changeless_iterations.to_string()),
+            ("calibration", self.calibration.as_ref().map_or("none".to_string(), ToString::to_string)),
+            ("restart", restart.unwrap_or_else(|| "none".to_string())),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+    }
+}
+
+/// Simulated Annealing implementation.
+pub struct Solution {
+    params: SimulatedAnnealingParams,
+    /// Fingerprint of `params` as given, before calibration changes temperatures.
+    fingerprint: Vec<(String, String)>,
+    budget: Budget,
+    incumbent: Option<Incumbent>,
+    iterations: u64,
+    evaluations: u64,
+    restarts: u32,
+    elapsed: Duration,
+    /// Lower bound of makespan of scheduled case, simulation stops once it's reached.
+    lower_bound: u128,
+    termination: Option<Termination>,
+    checkpoints: Option<Checkpoints>,
+    /// Checkpoint from which next run continues.
+    resume: Option<Checkpoint>,
+    resumed: Option<u64>,
+}
+
+impl Solution {
+    /// Creates simulation limited by `max_simulation_time` of `params` only.
+    pub fn new(params: SimulatedAnnealingParams) -> Self {
+        let budget = Budget::with_time(Duration::from_secs(params.max_simulation_time.into()));
+        Self {
+            fingerprint: params.fingerprint(),
+            params,
+            budget,
+            incumbent: None,
+            iterations: 0,
+            evaluations: 0,
+            restarts: 0,
+            elapsed: Duration::default(),
+            lower_bound: 0,
+            termination: None,
+            checkpoints: None,
+            resume: None,
+            resumed: None,
+        }
+    }
+
+    /// Limits simulation by `budget` instead of `max_simulation_time`,
+    /// sharing best makespan through budget's incumbent if it has one.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        if let Some(incumbent) = budget.incumbent() {
+            self.incumbent = Some(incumbent.clone());
+        }
+        self.budget = budget;
+        self
+    }
+
+    /// Shares best found makespan with other solvers through `incumbent`.
+    /// Simulation stops as soon as any of them reaches incumbent's lower bound.
+    pub fn with_incumbent(mut self, incumbent: Incumbent) -> Self {
+        self.incumbent = Some(incumbent);
+        self
+    }
+
+    /// Saves checkpoints of simulation as configured by `checkpoints`.
+    pub fn with_checkpoints(mut self, checkpoints: Checkpoints) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    /// Continues simulation from `checkpoint` instead of starting it from initial solution.
+    /// Parameters have to be the same as those of interrupted simulation,
+    /// only budget may differ (it counts time and iterations spent before checkpoint).
+    pub fn with_resume(mut self, checkpoint: Checkpoint) -> Self {
+        self.resume = Some(checkpoint);
+        self
+    }
+
+    /// Returns number of iterations performed by last run (including those before checkpoint it resumed from).
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    /// Returns number of neighbours evaluated by last run, including calibration samples.
+    pub fn evaluations(&self) -> u64 {
+        self.evaluations
+    }
+
+    /// Returns limit which stopped last run.
+    pub fn termination(&self) -> Option<Termination> {
+        self.termination
+    }
+
+    /// Returns number of restarts made by last run.
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// Returns initial and final temperature used by last run (after calibration).
+    pub fn temperatures(&self) -> (f64, f64) {
+        (self.params.initial_temperature, self.params.final_temperature)
+    }
+
+    /// Returns duration of last run.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns iteration at which last run resumed from checkpoint,
+    /// `None` if it started from initial solution.
+    pub fn resumed(&self) -> Option<u64> {
+        self.resumed
+    }
+
+    /// Returns lower bound of makespan used by last run.
+    pub fn lower_bound(&self) -> u128 {
+        self.lower_bound
+    }
+
+    pub fn run(&mut self, observer: &mut dyn Observer) -> Schedule {
+        let timer = Instant::now();
+        let cpu = CpuClock::start();
+
+        let case = Case::from(&self.params.initial_solution);
+        self.resumed = None;
+        let (mut rng, mut state) = match self.resume.take() {
+            Some(checkpoint) => match checkpoint.fits(&case).and_then(|()| checkpoint.matches(&self.fingerprint)) {
+                Ok(()) => {
+                    observer.comment(&format!(
+                        "resumed at iteration {} with best makespan {}",
+                        checkpoint.iterations(),
+                        checkpoint.best_makespan()
+                    ));
+                    self.resumed = Some(checkpoint.iterations());
+                    (checkpoint.generator.restore(), checkpoint)
+                }
+                Err(err) => {
+                    observer.comment(&format!("{} Starting from initial solution.", err));
+                    self.start(observer)
+                }
+            },
+            None => self.start(observer),
+        };
+        self.lower_bound = case.lower_bound();
+        let (initial_temperature, final_temperature) = state.temperatures;
+        self.params.initial_temperature = initial_temperature;
+        self.params.final_temperature = final_temperature;
+        let (wall_offset, cpu_offset) = (state.wall_time, state.cpu_time);
+        let mut saved = Instant::now();
+
+        let mut applied: Vec<Move> = Vec::new();
+        let mut proposed: Vec<Move> = Vec::new();
+        let mut step = CoolingStep {
+            step: state.step,
+            initial_temperature,
+            final_temperature,
+            ..CoolingStep::default()
+        };
+
+        self.offer(state.best.makespan());
+        observer.event(Event::Started, &progress(&state));
+        let termination = loop {
+            let usage = Usage {
+                wall_time: wall_offset + timer.elapsed(),
+                cpu_time: cpu_offset + cpu.elapsed(),
+                iterations: state.iteration - 1,
+                evaluations: state.evaluations,
+            };
+            let termination =
+                self.check_limits(state.temperature, &usage, state.changeless_iterations, state.best.makespan());
+            if let Some(checkpoints) = &self.checkpoints {
+                if termination.is_some() || saved.elapsed() >= checkpoints.interval {
+                    state.step = step.step;
+                    state.generator = GeneratorState::from(&rng);
+                    state.wall_time = usage.wall_time;
+                    state.cpu_time = usage.cpu_time;
+                    // Failed checkpoint shouldn't stop the simulation, next one may succeed.
+                    if let Err(err) = state.write_to_file(&checkpoints.path) {
+                        observer.comment(&format!("checkpoint failed: {}", err));
+                    }
+                    saved = Instant::now();
+                }
+            }
+            if let Some(termination) = termination {
+                break termination;
+            }
+
+            // Resumed step continues where it was interrupted.
+            while state.partial_step.iterations < self.params.iterations_per_temperature {
+                // Higher temperatures allow longer chains of neighbours.
+                // All but the last move are applied right away,
+                // the last one is only evaluated.
+                applied.clear();
+                let current_solution = &mut state.current;
+                let makespan = current_solution.makespan();
+                let steps = (state.temperature.log(5.0) + 1.0).max(1.0) as u64;
+                state.evaluations += steps;
+                let mut last = None;
+                for _ in 0..steps {
+                    if let Some(mv) = last.take() {
+                        current_solution.apply(mv);
+                        applied.push(mv);
+                    }
+                    proposed.clear();
+                    self.params.neighbourhood.propose(current_solution, &mut rng, &mut proposed);
+                    last = proposed.pop();
+                    for &mv in &proposed {
+                        current_solution.apply(mv);
+                        applied.push(mv);
+                    }
+                }
+                let candidate = last.map_or(current_solution.makespan(), |mv| current_solution.makespan_after(mv));
+
+                // Comparing candidate with current solution instead of the best one
+                // actually outputs better results.
+                let best = state.best.makespan();
+                let accepted_move = state.acceptor.accept(makespan, candidate, best, state.temperature, &mut rng);
+                if accepted_move {
+                    if let Some(mv) = last {
+                        current_solution.apply(mv);
+                    }
+                    state.changeless_iterations = 0;
+                    state.partial_step.accepted += 1;
+                } else {
+                    for mv in applied.iter().rev() {
+                        current_solution.undo(*mv);
+                    }
+                    state.changeless_iterations += 1;
+                }
+                let improved = current_solution.makespan() < best;
+                if improved {
+                    state.best = current_solution.clone();
+                    self.offer(state.best.makespan());
+                    state.since_improvement = 0;
+                } else {
+                    state.since_improvement += 1;
+                }
+
+                let makespan = current_solution.makespan() as f64;
+                state.partial_step.sum += makespan;
+                state.partial_step.sum_of_squares += makespan * makespan;
+                state.partial_step.iterations += 1;
+
+                state.iteration += 1;
+                let progress = progress(&state);
+                observer.event(Event::Moved { accepted: accepted_move }, &progress);
+                if improved {
+                    observer.event(Event::NewBest { previous: best }, &progress);
+                }
+                observer.record(state.iteration, progress.makespan);
+
+                if state.best.makespan() <= self.lower_bound
+                    || self.budget.counted_out(state.iteration - 1, state.evaluations).is_some()
+                {
+                    break;
+                }
+            }
+            // Step interrupted by a limit is neither cooled nor restarted,
+            // it's checkpointed as it is, so resumed run completes it like uninterrupted one.
+            if state.partial_step.iterations < self.params.iterations_per_temperature {
+                continue;
+            }
+
+            let PartialStep {
+                accepted,
+                sum,
+                sum_of_squares,
+                ..
+            } = std::mem::take(&mut state.partial_step);
+            let count = f64::from(self.params.iterations_per_temperature.max(1));
+            step.step += 1;
+            step.elapsed = self.budget.fraction(&Usage {
+                wall_time: wall_offset + timer.elapsed(),
+                cpu_time: cpu_offset + cpu.elapsed(),
+                iterations: state.iteration - 1,
+                evaluations: state.evaluations,
+            });
+            step.acceptance = f64::from(accepted) / count;
+            step.deviation = (sum_of_squares / count - (sum / count).powi(2)).max(0.0).sqrt();
+            let previous = state.temperature;
+            state.temperature = self.reduce_temperature(state.temperature, &step);
+            observer.event(Event::TemperatureChanged { previous }, &progress(&state));
+
+            if let Some(policy) = &self.params.restart {
+                if policy.is_due(state.since_improvement, state.restarts) {
+                    state.temperature = match policy.strategy {
+                        Restart::Reheat(fraction) => fraction * self.params.initial_temperature,
+                        Restart::Best => {
+                            state.current = state.best.clone();
+                            self.params.initial_temperature
+                        }
+                        Restart::Fresh(start) => {
+                            state.current = Assignment::from(&start.build(&case, &mut rng));
+                            self.params.initial_temperature
+                        }
+                    };
+                    state.acceptor = Acceptor::new(self.params.acceptance, state.current.makespan(), state.temperature);
+                    state.restarts += 1;
+                    step.step = 0;
+                    state.since_improvement = 0;
+                    state.changeless_iterations = 0;
+                    observer.comment(&format!(
+                        "restart {} ({}) at iteration {}: temperature {}, makespan {}, best {}",
+                        state.restarts,
+                        policy.strategy,
+                        state.iteration - 1,
+                        state.temperature,
+                        state.current.makespan(),
+                        state.best.makespan()
+                    ));
+                    observer.event(Event::Restarted { restarts: state.restarts }, &progress(&state));
+                }
+            }
+        };
+        if state.best.makespan() <= self.lower_bound {
+            observer.comment(&format!(
+                "lower bound {} reached at iteration {}, solution is optimal",
+                self.lower_bound,
+                state.iteration - 1
+            ));
+        } else {
+            observer.comment(&format!("stopped by {} at iteration {}", termination, state.iteration - 1));
+        }
+        observer.event(Event::Finished { termination }, &progress(&state));
+        observer.finish();
+        self.iterations = state.iteration - 1;
+        self.evaluations = state.evaluations;
+        self.restarts = state.restarts;
+        self.termination = Some(termination);
+        self.elapsed = wall_offset + timer.elapsed();
+        state.best.to_schedule()
+    }
+
+    /// Calibrates temperatures and returns generator and state of fresh simulation.
+    fn start(&mut self, observer: &mut dyn Observer) -> (ChaCha12Rng, Checkpoint) {
+        let mut rng = ChaCha12Rng::seed_from_u64(self.params.seed);
+        let current = Assignment::from(&self.params.initial_solution);
+        let mut evaluations = 0u64;
+
+        if let Some(calibration) = &self.params.calibration {
+            evaluations += calibration.samples as u64;
+            match calibration.calibrate(&current, &self.params.neighbourhood, &mut rng) {
+                Some((initial, last)) => {
+                    self.params.initial_temperature = initial;
+                    self.params.final_temperature = last.unwrap_or(self.params.final_temperature);
+                    observer.comment(&format!(
+                        "calibrated initial temperature {}, final temperature {}",
+                        self.params.initial_temperature, self.params.final_temperature
+                    ));
+                }
+                None => observer.comment(&format!(
+                    "calibration found no worsening moves, keeping initial temperature {}, final temperature {}",
+                    self.params.initial_temperature, self.params.final_temperature
+                )),
+            }
+        }
+        observer.record(0, current.makespan());
+
+        let state = Checkpoint {
+            seed: self.params.seed,
+            parameters: self.fingerprint.clone(),
+            best: current.clone(),
+            temperature: self.params.initial_temperature,
+            temperatures: (self.params.initial_temperature, self.params.final_temperature),
+            iteration: 1,
+            evaluations,
+            changeless_iterations: 0,
+            since_improvement: 0,
+            restarts: 0,
+            step: 0,
+            partial_step: PartialStep::default(),
+            acceptor: Acceptor::new(self.params.acceptance, current.makespan(), self.params.initial_temperature),
+            generator: GeneratorState::from(&rng),
+            wall_time: Duration::default(),
+            cpu_time: Duration::default(),
+            current,
+        };
+        (rng, state)
+    }
+
+    fn offer(&self, makespan: u128) {
+        if let Some(incumbent) = &self.incumbent {
+            incumbent.offer(makespan);
+        }
+    }
+
+    /// Returns reason to stop simulation, if there is any.
+    fn check_limits(&self, temperature: f64, usage: &Usage, changeless_iterations: u16, best: u128) -> Option<Termination> {
+        if best <= self.lower_bound || self.incumbent.as_ref().is_some_and(Incumbent::is_optimal) {
+            Some(Termination::LowerBound)
+        } else if let Some(limit) = self.budget.exceeded(usage) {
+            Some(limit)
+        } else if temperature <= self.params.final_temperature {
+            Some(Termination::Temperature)
+        } else if changeless_iterations >= self.params.max_changeless_iterations {
+            Some(Termination::Stagnation)
+        } else {
+            None
+        }
+    }
+
+    fn reduce_temperature(&self, temperature: f64, step: &CoolingStep) -> f64 {
+        self.params.reduction_rule.reduce(temperature, step)
+    }
+}
+
+/// Simulated Annealing as a solver.
+pub struct Annealing {
+    params: SimulatedAnnealingParams,
+    start: InitialSolution,
+    checkpoints: Option<Checkpoints>,
+    resume: Option<Checkpoint>,
+}
+
+impl Annealing {
+    /// Creates solver running with `params`.
+    /// When no initial schedule is given to `solve`, one of `start` kind is built.
+    pub fn new(params: SimulatedAnnealingParams, start: InitialSolution) -> Self {
+        Self {
+            params,
+            start,
+            checkpoints: None,
+            resume: None,
+        }
+    }
+
+    /// Saves checkpoints of simulation as configured by `checkpoints`.
+    pub fn with_checkpoints(mut self, checkpoints: Checkpoints) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    /// Continues simulation from `checkpoint`, with initial solution built from checkpoint's seed.
+    pub fn with_resume(mut self, checkpoint: Checkpoint) -> Self {
+        self.resume = Some(checkpoint);
+        self
+    }
+}
+
+impl Solver for Annealing {
+    fn name(&self) -> &'static str {
+        "sa"
+    }
+
+    fn solve(&self, case: &Case, initial: Option<Schedule>, budget: &Budget, observer: &mut dyn Observer) -> Outcome {
+        let seed = self.resume.as_ref().map_or(self.params.seed, Checkpoint::seed);
+        let initial_solution = initial.unwrap_or_else(|| self.start.build(case, &mut ChaCha12Rng::seed_from_u64(seed)));
+        let params = SimulatedAnnealingParams {
+            initial_solution,
+            seed,
+            ..self.params.clone()
+        };
+
+        let mut solution = Solution::new(params).with_budget(budget.clone());
+        if let Some(checkpoints) = &self.checkpoints {
+            solution = solution.with_checkpoints(checkpoints.clone());
+        }
+        if let Some(checkpoint) = &self.resume {
+            solution = solution.with_resume(checkpoint.clone());
+        }
+        let schedule = solution.run(observer);
+
+        let mut details = vec![format!("Evaluations: {}", solution.evaluations())];
+        match (&self.resume, solution.resumed()) {
+            (Some(_), Some(iteration)) => details.push(format!("Resumed from checkpoint at iteration {}", iteration)),
+            (Some(_), None) => details.push("Checkpoint doesn't fit case, started from initial solution".to_string()),
+            _ => {}
+        }
+        if self.params.calibration.is_some() {
+            let (initial, last) = solution.temperatures();
+            details.push(format!("Calibrated temperatures: initial {:.4}, final {:.4}", initial, last));
+        }
+        if self.params.acceptance != Acceptance::Metropolis {
+            details.push(format!("Acceptance: {}", self.params.acceptance));
+        }
+        if self.params.restart.is_some() {
+            details.push(format!("Restarts: {}", solution.restarts()));
+        }
+
+        Outcome {
+            stats: SolverStats {
+                iterations: solution.iterations(),
+                elapsed: solution.elapsed(),
+                details,
+                optimal: schedule.makespan().unwrap() <= solution.lower_bound(),
+                termination: solution.termination(),
+            },
+            schedule,
+        }
+    }
+}
+
+/// Describes state of running simulation for observers.
+fn progress(state: &Checkpoint) -> Progress<'_> {
+    Progress {
+        iteration: state.iteration - 1,
+        temperature: state.temperature,
+        makespan: state.current.makespan(),
+        best: state.best.makespan(),
+        solution: Some(&state.current),
+    }
+}
+
+/// Builds Simulated Annealing solver, or its multi-start variant when more than one run is requested.
+pub fn solver(settings: &Settings) -> Result<Box<dyn Solver>, String> {
+    if settings.runs > 1 {
+        return multistart::solver(settings);
+    }
+
+    let params = SimulatedAnnealingParams {
+        seed: settings.seed,
+        max_simulation_time: settings.kill_time,
+        ..settings.annealing.apply(SimulatedAnnealingParams::default())
+    };
+    let start = settings.starts.first().copied().unwrap_or(InitialSolution::Random);
+
+    let mut annealing = Annealing::new(params, start);
+    if let Some(path) = &settings.checkpoint {
+        annealing = annealing.with_checkpoints(Checkpoints {
+            path: path.into(),
+            interval: settings.checkpoint_interval,
+        });
+    }
+    if let Some(path) = &settings.resume {
+        let checkpoint = read_checkpoint(path)?;
+        checkpoint.fits_params(&annealing.params)?;
+        annealing = annealing.with_resume(checkpoint);
+    }
+
+    Ok(Box::new(annealing))
+}
+
+/// Proposes move of single task between two different cores.
+///
+/// With probability `alpha` every choice is biased: task is taken from the most loaded core,
+/// it's the shortest task there and it goes to the least loaded core.
+/// Otherwise respective choice is random.
+/// Returns `None` when there are less than two cores or no tasks at all.
+pub fn neighbour<R: Rng>(solution: &Assignment, rng: &mut R) -> Option<Move> {
+    neighbourhood::biased_move(solution, 0.60, rng)
+}
+
+#[cfg(test)]
+mod test_simulated_annealing {
+    use crate::observers::{Events, Observers};
+    use crate::utils::{Core, Task};
+
+    use super::*;
+
+    #[test]
+    fn test_neighbour_cores() {
+        let mut initial = Schedule::new();
+
+        let mut first_core = Core::new();
+        let mut second_core = Core::new();
+
+        first_core.add_task(Task::with_length(1));
+        second_core.add_task(Task::with_length(3));
+
+        let mut rng = ChaCha12Rng::seed_from_u64(0);
+
+        initial.add_core(first_core);
+        assert!(neighbour(&Assignment::from(&initial), &mut rng).is_none());
+
+        initial.add_core(second_core);
+        assert!(neighbour(&Assignment::from(&initial), &mut rng).is_some());
+    }
+
+    #[test]
+    fn test_run_keeps_tasks() {
+        let mut initial = Schedule::new();
+        initial.add_core(Core::from((1..30).map(Task::with_length).collect::<Vec<_>>()));
+        initial.add_core(Core::new());
+        initial.add_core(Core::new());
+
+        let params = SimulatedAnnealingParams {
+            initial_solution: initial,
+            initial_temperature: 50.0,
+            final_temperature: 0.1,
+            reduction_rule: Reduction::Geometric(0.9),
+            neighbourhood: "biased:0.6=2,swap,two-opt,ejection:2".parse().unwrap(),
+            acceptance: Acceptance::Metropolis,
+            iterations_per_temperature: 20,
+            max_changeless_iterations: 1000,
+            max_simulation_time: 5,
+            seed: 11,
+            calibration: None,
+            restart: None,
+        };
+        let best = Solution::new(params).run(&mut ());
+
+        let mut lengths: Vec<u64> = best
+            .cores()
+            .iter()
+            .flat_map(|core| core.get_tasks())
+            .map(|task| task.length())
+            .collect();
+        lengths.sort_unstable();
+
+        assert_eq!(lengths, (1..30).collect::<Vec<u64>>());
+        assert!(best.makespan().unwrap() < 435);
+    }
+
+    #[test]
+    fn test_stops_at_lower_bound() {
+        let mut initial = Schedule::new();
+        initial.add_core(Core::from(vec![Task::with_length(5), Task::with_length(4)]));
+        initial.add_core(Core::from(vec![Task::with_length(3)]));
+        initial.add_core(Core::new());
+        let case = Case::from(&initial);
+
+        let outcome = Annealing::new(SimulatedAnnealingParams::default(), InitialSolution::Random).solve(
+            &case,
+            Some(initial),
+            &Budget::with_time(Duration::from_secs(5)),
+            &mut (),
+        );
+
+        assert!(outcome.stats.optimal);
+        assert_eq!(outcome.schedule.makespan().unwrap(), case.lower_bound());
+        assert!(outcome.stats.iterations < u64::from(SimulatedAnnealingParams::default().iterations_per_temperature));
+    }
+
+    #[test]
+    fn test_same_seed_same_run() {
+        let mut case = Case::new().with_cores(4);
+        case.add_tasks((1..40).map(|length| Task::with_length(length * 7 % 23 + 1)).collect());
+        let annealing = Annealing::new(
+            SimulatedAnnealingParams {
+                seed: 5,
+                neighbourhood: "biased:0.6,swap,ejection:2".parse().unwrap(),
+                ..SimulatedAnnealingParams::default()
+            },
+            InitialSolution::Random,
+        );
+        let budget = Budget::with_time(Duration::from_secs(30));
+
+        let first = annealing.solve(&case, None, &budget, &mut ());
+        let second = annealing.solve(&case, None, &budget, &mut ());
+
+        assert_eq!(first.schedule, second.schedule);
+        assert_eq!(first.stats.iterations, second.stats.iterations);
+    }
+
+    #[test]
+    fn test_iteration_budget() {
+        // Lower bound 15 can't be reached, so only budget stops simulation.
+        let mut case = Case::new().with_cores(2);
+        case.add_tasks(vec![Task::with_length(10); 3]);
+        let budget = Budget::with_time(Duration::from_secs(30)).with_iterations(100);
+
+        let outcome = Annealing::new(SimulatedAnnealingParams::default(), InitialSolution::Random)
+            .solve(&case, None, &budget, &mut ());
+
+        assert_eq!(outcome.stats.iterations, 100);
+        assert_eq!(outcome.stats.termination, Some(Termination::Iterations));
+        assert!(!outcome.stats.optimal);
+    }
+
+    #[test]
+    fn test_events() {
+        let mut case = Case::new().with_cores(2);
+        case.add_tasks(vec![Task::with_length(10); 3]);
+        let budget = Budget::with_time(Duration::from_secs(30)).with_iterations(95);
+        let params = SimulatedAnnealingParams {
+            iterations_per_temperature: 10,
+            ..SimulatedAnnealingParams::default()
+        };
+        let mut events = Events::new();
+
+        Annealing::new(params, InitialSolution::Random).solve(&case, None, &budget, &mut events);
+
+        let occurrences = events.occurrences();
+        assert_eq!(occurrences.first().unwrap().event, Event::Started);
+        assert_eq!(
+            occurrences.last().unwrap().event,
+            Event::Finished {
+                termination: Termination::Iterations
+            }
+        );
+        assert_eq!(events.count(|event| matches!(event, Event::Moved { .. })), 95);
+        // Last step, interrupted after 5 iterations, isn't cooled.
+        assert_eq!(events.count(|event| matches!(event, Event::TemperatureChanged { .. })), 9);
+        for occurrence in occurrences {
+            if let Event::NewBest { previous } = occurrence.event {
+                assert!(occurrence.best < previous);
+                assert_eq!(occurrence.best, occurrence.makespan);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resume_continues_identically() {
+        // Some core gets three tasks, so lower bound 31 can't be reached and only budget stops simulation.
+        let mut case = Case::new().with_cores(3);
+        case.add_tasks((10..17).map(Task::with_length).collect());
+        let path = std::env::temp_dir().join(format!("max-resume-{}.json", std::process::id()));
+        let annealing = || {
+            Annealing::new(
+                SimulatedAnnealingParams {
+                    seed: 3,
+                    neighbourhood: "biased:0.6,swap,two-opt".parse().unwrap(),
+                    acceptance: Acceptance::Late(5),
+                    ..SimulatedAnnealingParams::default()
+                },
+                InitialSolution::Random,
+            )
+        };
+        let budget = |iterations| Budget::with_time(Duration::from_secs(30)).with_iterations(iterations);
+
+        let mut whole_temperatures = Temperatures::default();
+        let whole = annealing().solve(&case, None, &budget(400), &mut whole_temperatures);
+
+        // Steps are 20 iterations long, so the second run is interrupted in the middle of one.
+        for &interruption in &[200, 210] {
+            let checkpoints = Checkpoints {
+                path: path.clone(),
+                interval: Duration::from_secs(60),
+            };
+            let mut temperatures = Temperatures::default();
+            annealing().with_checkpoints(checkpoints).solve(&case, None, &budget(interruption), &mut temperatures);
+            let checkpoint = Checkpoint::read_from_file(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(checkpoint.iterations(), interruption);
+
+            let resumed = annealing().with_resume(checkpoint).solve(&case, None, &budget(400), &mut temperatures);
+
+            assert_eq!(temperatures.0, whole_temperatures.0);
+            assert_eq!(resumed.schedule, whole.schedule);
+            assert_eq!(resumed.stats.iterations, 400);
+            assert_eq!(resumed.stats.details[0], whole.stats.details[0]);
+            let comment = format!("Resumed from checkpoint at iteration {}", interruption);
+            assert!(resumed.stats.details.contains(&comment));
+        }
+    }
+
+    /// Keeps iterations and temperatures of temperature changes.
+    #[derive(Default)]
+    struct Temperatures(Vec<(u64, f64)>);
+
+    impl Observer for Temperatures {
+        fn event(&mut self, event: Event, progress: &Progress) {
+            if let Event::TemperatureChanged { .. } = event {
+                self.0.push((progress.iteration, progress.temperature));
+            }
+        }
+    }
+
+    /// Keeps comments written by simulation.
+    #[derive(Default)]
+    struct Comments(Vec<String>);
+
+    impl Observer for Comments {
+        fn comment(&mut self, comment: &str) {
+            self.0.push(comment.to_string());
+        }
+    }
+
+    #[test]
+    fn test_restarts_after_stagnation() {
+        // Lower bound 31 can't be reached, so simulation stagnates and restarts.
+        let mut case = Case::new().with_cores(3);
+        case.add_tasks((10..17).map(Task::with_length).collect());
+        let params = SimulatedAnnealingParams {
+            initial_solution: InitialSolution::Random.build(&case, &mut ChaCha12Rng::seed_from_u64(1)),
+            iterations_per_temperature: 10,
+            seed: 1,
+            restart: Some(RestartPolicy::new(Restart::Reheat(0.5), 30).with_max_restarts(2)),
+            ..SimulatedAnnealingParams::default()
+        };
+        let budget = Budget::with_time(Duration::from_secs(30)).with_iterations(3000);
+        let (mut events, mut comments) = (Events::new(), Comments::default());
+
+        let mut solution = Solution::new(params).with_budget(budget);
+        solution.run(&mut Observers::new().with(&mut events).with(&mut comments));
+
+        assert_eq!(solution.restarts(), 2);
+        let restarts: Vec<u32> = events
+            .occurrences()
+            .iter()
+            .filter_map(|occurrence| match occurrence.event {
+                Event::Restarted { restarts } => Some(restarts),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(restarts, vec![1, 2]);
+        let restarted: Vec<&String> = comments.0.iter().filter(|comment| comment.starts_with("restart ")).collect();
+        assert_eq!(restarted.len(), 2);
+        assert!(restarted[0].starts_with("restart 1 (reheat:0.5) at iteration "), "{}", restarted[0]);
+        assert!(restarted[1].contains("temperature 37.5"), "{}", restarted[1]);
+    }
+}
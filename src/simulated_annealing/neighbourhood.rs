@@ -0,0 +1,411 @@
+use crate::utils::{Assignment, Move};
+use rand::{seq::index, Rng};
+use std::{fmt, str::FromStr};
+
+/// Way of proposing neighbouring solution.\
+/// Biased Move(α): moves single task, every choice (the most loaded source core,
+/// the least loaded target core, the shortest task) is made with probability α, otherwise it's random\
+/// Random Move: moves random task to random other core\
+/// Swap: exchanges random task of the most loaded core with random task of another core\
+/// Two-Opt: cuts task lists of the most loaded core and random other core at random points
+/// and exchanges their tails\
+/// Ejection Chain(k): moves random task of the most loaded core to another core,
+/// which in turn ejects one of its tasks to the next core, k moves at most
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Operator {
+    BiasedMove(f64),
+    RandomMove,
+    Swap,
+    TwoOpt,
+    EjectionChain(usize),
+}
+
+impl Operator {
+    /// Pushes moves making up proposed neighbour of `solution` onto `moves`.
+    /// Moves have to be applied in order, nothing is pushed when operator can't be applied.
+    pub fn propose<R: Rng>(&self, solution: &Assignment, rng: &mut R, moves: &mut Vec<Move>) {
+        if solution.cores() < 2 || solution.tasks() == 0 {
+            return;
+        }
+
+        match *self {
+            Self::BiasedMove(alpha) => moves.extend(biased_move(solution, alpha, rng)),
+            Self::RandomMove => moves.extend(biased_move(solution, 0.0, rng)),
+            Self::Swap => {
+                let from = match loaded_core(solution) {
+                    Some(core) => core,
+                    None => return,
+                };
+                if let Some(to) = other_core(solution, from, rng) {
+                    let first = random_task(solution, from, rng);
+                    let second = random_task(solution, to, rng);
+                    moves.push(Move::Swap { first, second });
+                }
+            }
+            Self::TwoOpt => {
+                let from = match loaded_core(solution) {
+                    Some(core) => core,
+                    None => return,
+                };
+                let to = random_core_except(solution.cores(), from, rng);
+                let (first, second) = (solution.tasks_on(from), solution.tasks_on(to));
+                let first_cut = rng.gen_range(0..first.len());
+                let second_cut = rng.gen_range(0..=second.len());
+
+                moves.extend(first[first_cut..].iter().map(|&task| Move::Transfer { task, from, to }));
+                moves.extend(second[second_cut..].iter().map(|&task| Move::Transfer { task, from: to, to: from }));
+            }
+            Self::EjectionChain(length) => {
+                let start = match loaded_core(solution) {
+                    Some(core) => core,
+                    None => return,
+                };
+                let (mut from, mut task) = (start, random_task(solution, start, rng));
+                let length = length.clamp(1, solution.cores() - 1);
+
+                // Every core takes part in the chain at most once.
+                for to in index::sample(rng, solution.cores() - 1, length) {
+                    let to = if to >= start { to + 1 } else { to };
+                    moves.push(Move::Transfer { task, from, to });
+                    // Chain ends on a core with nothing to eject.
+                    if solution.tasks_on(to).is_empty() {
+                        break;
+                    }
+                    task = random_task(solution, to, rng);
+                    from = to;
+                }
+            }
+        }
+    }
+}
+
+/// Proposes move of single task between two different cores, biased with probability `alpha`.
+pub(super) fn biased_move<R: Rng>(solution: &Assignment, alpha: f64, rng: &mut R) -> Option<Move> {
+    let cores = solution.cores();
+
+    if cores < 2 || solution.tasks() == 0 {
+        return None;
+    }
+
+    // first core index
+    let from = if rng.gen::<f64>() > alpha {
+        loop {
+            let core = rng.gen_range(0..cores);
+            if !solution.tasks_on(core).is_empty() {
+                break core;
+            }
+        }
+    } else {
+        loaded_core(solution)?
+    };
+
+    // second core index
+    let to = if rng.gen::<f64>() > alpha {
+        random_core_except(cores, from, rng)
+    } else {
+        solution.loads().min_core_excluding(&[from])?
+    };
+
+    // task moved from first core to the second one
+    let tasks = solution.tasks_on(from);
+    let task = if rng.gen::<f64>() > alpha {
+        tasks[rng.gen_range(0..tasks.len())]
+    } else {
+        *tasks.iter().min_by_key(|&&task| solution.length(task))?
+    };
+
+    Some(Move::Transfer { task, from, to })
+}
+
+/// Returns the most loaded core, or the first non-empty one when every task has zero length.
+fn loaded_core(solution: &Assignment) -> Option<usize> {
+    match solution.loads().max_core() {
+        Some(core) if !solution.tasks_on(core).is_empty() => Some(core),
+        _ => (0..solution.cores()).find(|&core| !solution.tasks_on(core).is_empty()),
+    }
+}
+
+fn random_core_except<R: Rng>(cores: usize, except: usize, rng: &mut R) -> usize {
+    let core = rng.gen_range(0..cores - 1);
+    if core >= except { core + 1 } else { core }
+}
+
+fn random_task<R: Rng>(solution: &Assignment, core: usize, rng: &mut R) -> usize {
+    let tasks = solution.tasks_on(core);
+    tasks[rng.gen_range(0..tasks.len())]
+}
+
+/// Returns random non-empty core other than `except`, if there is any.
+fn other_core<R: Rng>(solution: &Assignment, except: usize, rng: &mut R) -> Option<usize> {
+    let cores = solution.cores();
+    // Random probes first, so that scanning is needed only for almost empty schedules.
+    for _ in 0..cores {
+        let core = random_core_except(cores, except, rng);
+        if !solution.tasks_on(core).is_empty() {
+            return Some(core);
+        }
+    }
+    (0..cores).find(|&core| core != except && !solution.tasks_on(core).is_empty())
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BiasedMove(alpha) => write!(f, "biased:{}", alpha),
+            Self::RandomMove => write!(f, "random"),
+            Self::Swap => write!(f, "swap"),
+            Self::TwoOpt => write!(f, "two-opt"),
+            Self::EjectionChain(length) => write!(f, "ejection:{}", length),
+        }
+    }
+}
+
+/// Parses `biased:ALPHA`, `random`, `swap`, `two-opt` or `ejection:LENGTH`, as written by `Display`.
+impl FromStr for Operator {
+    type Err = String;
+
+    fn from_str(serialized: &str) -> Result<Self, Self::Err> {
+        match serialized.split_once(':') {
+            Some(("biased", alpha)) => alpha
+                .parse::<f64>()
+                .ok()
+                .filter(|alpha| (0.0..=1.0).contains(alpha))
+                .map(Self::BiasedMove)
+                .ok_or_else(|| format!("Invalid bias `{}`, it has to be from range [0;1].", alpha)),
+            Some(("ejection", length)) => length
+                .parse::<usize>()
+                .ok()
+                .filter(|&length| length > 0)
+                .map(Self::EjectionChain)
+                .ok_or_else(|| format!("Invalid ejection chain length `{}`.", length)),
+            Some(_) => Err(format!("Unknown neighbourhood operator `{}`.", serialized)),
+            None => match serialized {
+                "random" => Ok(Self::RandomMove),
+                "swap" => Ok(Self::Swap),
+                "two-opt" => Ok(Self::TwoOpt),
+                "biased" => Err("Operator `biased` requires parameter, e.g. `biased:0.6`.".to_string()),
+                "ejection" => Err("Operator `ejection` requires parameter, e.g. `ejection:3`.".to_string()),
+                _ => Err(format!("Unknown neighbourhood operator `{}`.", serialized)),
+            },
+        }
+    }
+}
+
+/// Operators used by annealing, each one chosen with probability proportional to its weight.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Neighbourhood {
+    operators: Vec<(Operator, f64)>,
+    /// Cumulative weights of operators.
+    thresholds: Vec<f64>,
+}
+
+impl Neighbourhood {
+    /// Creates neighbourhood of weighted `operators`.
+    /// At least one weight has to be positive and none of them negative.
+    pub fn new(operators: Vec<(Operator, f64)>) -> Result<Self, String> {
+        if operators.iter().any(|&(_, weight)| !(weight >= 0.0 && weight.is_finite())) {
+            return Err("Operator weights have to be non-negative numbers.".to_string());
+        }
+        let thresholds: Vec<f64> = operators
+            .iter()
+            .scan(0.0, |total, &(_, weight)| {
+                *total += weight;
+                Some(*total)
+            })
+            .collect();
+        if thresholds.last().is_none_or(|&total| total <= 0.0) {
+            return Err("At least one operator needs positive weight.".to_string());
+        }
+
+        Ok(Self { operators, thresholds })
+    }
+
+    pub fn operators(&self) -> &[(Operator, f64)] {
+        &self.operators
+    }
+
+    /// Pushes moves of neighbour proposed by randomly chosen operator onto `moves`.
+    pub fn propose<R: Rng>(&self, solution: &Assignment, rng: &mut R, moves: &mut Vec<Move>) {
+        let operator = if self.operators.len() == 1 {
+            &self.operators[0].0
+        } else {
+            let point = rng.gen::<f64>() * self.thresholds[self.thresholds.len() - 1];
+            let chosen = self.thresholds.partition_point(|&threshold| threshold <= point);
+            &self.operators[chosen.min(self.operators.len() - 1)].0
+        };
+        operator.propose(solution, rng, moves);
+    }
+}
+
+/// Biased move with α = 0.6 only.
+impl Default for Neighbourhood {
+    fn default() -> Self {
+        Self::new(vec![(Operator::BiasedMove(0.6), 1.0)]).unwrap()
+    }
+}
+
+impl fmt::Display for Neighbourhood {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (operator, weight)) in self.operators.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}={}", operator, weight)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses comma separated `OPERATOR[=WEIGHT]` list, e.g. `biased:0.6=3,swap,ejection:3=0.5`.
+/// Weight defaults to 1.
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    fn from_str(serialized: &str) -> Result<Self, Self::Err> {
+        let operators = serialized
+            .split(',')
+            .map(str::trim)
+            .map(|entry| match entry.split_once('=') {
+                Some((operator, weight)) => Ok((
+                    operator.parse()?,
+                    weight
+                        .parse::<f64>()
+                        .map_err(|_| format!("Invalid weight `{}` of operator `{}`.", weight, operator))?,
+                )),
+                None => Ok((entry.parse()?, 1.0)),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Self::new(operators)
+    }
+}
+
+#[cfg(test)]
+mod test_neighbourhood {
+    use super::*;
+    use crate::utils::{Core, Schedule, Task};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn assignment() -> Assignment {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from((1..10).map(Task::with_length).collect::<Vec<_>>()));
+        schedule.add_core(Core::from(vec![Task::with_length(4), Task::with_length(6)]));
+        schedule.add_core(Core::from(vec![Task::with_length(2)]));
+        schedule.add_core(Core::new());
+        Assignment::from(&schedule)
+    }
+
+    #[test]
+    fn test_operators_keep_tasks() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut moves = Vec::new();
+
+        for operator in &[
+            Operator::BiasedMove(0.6),
+            Operator::RandomMove,
+            Operator::Swap,
+            Operator::TwoOpt,
+            Operator::EjectionChain(3),
+        ] {
+            for _ in 0..50 {
+                let mut solution = assignment();
+                moves.clear();
+                operator.propose(&solution, &mut rng, &mut moves);
+                assert!(!moves.is_empty(), "{} proposed nothing", operator);
+
+                let (last, applied) = moves.split_last().unwrap();
+                for mv in applied {
+                    solution.apply(*mv);
+                }
+                let predicted = solution.makespan_after(*last);
+                solution.apply(*last);
+                assert_eq!(predicted, solution.makespan());
+
+                for mv in moves.iter().rev() {
+                    solution.undo(*mv);
+                }
+                let cores: Vec<usize> = (0..solution.tasks()).map(|task| solution.core_of(task)).collect();
+                let initial = assignment();
+                assert_eq!(cores, (0..initial.tasks()).map(|task| initial.core_of(task)).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_length_tasks() {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::new());
+        schedule.add_core(Core::from(vec![Task::with_length(0), Task::with_length(0)]));
+        schedule.add_core(Core::new());
+        let solution = Assignment::from(&schedule);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut moves = Vec::new();
+
+        for operator in &[
+            Operator::BiasedMove(1.0),
+            Operator::RandomMove,
+            Operator::Swap,
+            Operator::TwoOpt,
+            Operator::EjectionChain(2),
+        ] {
+            for _ in 0..20 {
+                let mut solution = solution.clone();
+                moves.clear();
+                operator.propose(&solution, &mut rng, &mut moves);
+                // Swap needs two non-empty cores.
+                assert_eq!(moves.is_empty(), *operator == Operator::Swap, "{}", operator);
+                for mv in &moves {
+                    solution.apply(*mv);
+                }
+                assert_eq!(solution.makespan(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_core() {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from(vec![Task::with_length(1)]));
+        let mut moves = Vec::new();
+
+        "biased:0.6,random,swap,two-opt,ejection:2"
+            .parse::<Neighbourhood>()
+            .unwrap()
+            .propose(&Assignment::from(&schedule), &mut StdRng::seed_from_u64(0), &mut moves);
+
+        assert!(moves.is_empty());
+    }
+
+    #[test]
+    fn test_neighbourhood_from_str() {
+        let neighbourhood: Neighbourhood = "biased:0.6=3, swap, ejection:3=0.5".parse().unwrap();
+
+        assert_eq!(
+            neighbourhood.operators(),
+            &[
+                (Operator::BiasedMove(0.6), 3.0),
+                (Operator::Swap, 1.0),
+                (Operator::EjectionChain(3), 0.5)
+            ]
+        );
+        assert_eq!(neighbourhood.to_string().parse(), Ok(neighbourhood));
+        assert!("biased:1.5".parse::<Neighbourhood>().is_err());
+        assert!("swap=0".parse::<Neighbourhood>().is_err());
+        assert!("swap=-1,random".parse::<Neighbourhood>().is_err());
+        assert!("shuffle".parse::<Neighbourhood>().is_err());
+    }
+
+    #[test]
+    fn test_weights() {
+        let neighbourhood: Neighbourhood = "swap=0,random".parse().unwrap();
+        let solution = assignment();
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut moves = Vec::new();
+
+        for _ in 0..100 {
+            moves.clear();
+            neighbourhood.propose(&solution, &mut rng, &mut moves);
+            assert!(matches!(moves[..], [Move::Transfer { .. }]));
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::multistart::InitialSolution;
+use std::{fmt, str::FromStr};
+
+/// What happens when annealing stagnates.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Restart {
+    /// Temperature is raised to given fraction of initial temperature,
+    /// search continues from current solution.
+    Reheat(f64),
+    /// Search continues from best solution found so far at initial temperature.
+    Best,
+    /// Search continues from newly built solution at initial temperature.
+    Fresh(InitialSolution),
+}
+
+impl fmt::Display for Restart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reheat(fraction) => write!(f, "reheat:{}", fraction),
+            Self::Best => write!(f, "best"),
+            Self::Fresh(start) => write!(f, "{}", start),
+        }
+    }
+}
+
+/// Parses `reheat:FRACTION`, `best` or name of initial solution kind.
+impl FromStr for Restart {
+    type Err = String;
+
+    fn from_str(serialized: &str) -> Result<Self, Self::Err> {
+        match serialized.split_once(':') {
+            Some(("reheat", fraction)) => fraction
+                .parse::<f64>()
+                .ok()
+                .filter(|fraction| *fraction > 0.0)
+                .map(Self::Reheat)
+                .ok_or_else(|| format!("Invalid reheat fraction `{}`.", fraction)),
+            Some(_) => Err(format!("Unknown restart strategy `{}`.", serialized)),
+            None if serialized == "best" => Ok(Self::Best),
+            None => serialized
+                .parse::<InitialSolution>()
+                .map(Self::Fresh)
+                .map_err(|_| format!("Unknown restart strategy `{}`.", serialized)),
+        }
+    }
+}
+
+/// When and how annealing is restarted.
+///
+/// Restart happens once best solution hasn't improved for `stagnation` iterations.
+/// It's checked after every temperature step, so when `stagnation` is longer than
+/// cooling down to final temperature, simulation ends before any restart.
+#[derive(PartialEq, Debug, Clone)]
+pub struct RestartPolicy {
+    pub strategy: Restart,
+    pub stagnation: u64,
+    /// Maximal number of restarts, unlimited when `None`.
+    pub max_restarts: Option<u32>,
+}
+
+impl RestartPolicy {
+    pub fn new(strategy: Restart, stagnation: u64) -> Self {
+        Self {
+            strategy,
+            stagnation,
+            max_restarts: None,
+        }
+    }
+
+    pub fn with_max_restarts(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = Some(max_restarts);
+        self
+    }
+
+    /// Returns `true` if restart should happen after `since_improvement` iterations
+    /// without improvement, when `restarts` restarts were already made.
+    pub fn is_due(&self, since_improvement: u64, restarts: u32) -> bool {
+        since_improvement >= self.stagnation && self.max_restarts.is_none_or(|max| restarts < max)
+    }
+}
+
+#[cfg(test)]
+mod test_restart {
+    use super::*;
+
+    #[test]
+    fn test_restart_from_str() {
+        assert_eq!("reheat:0.5".parse(), Ok(Restart::Reheat(0.5)));
+        assert_eq!("best".parse(), Ok(Restart::Best));
+        assert_eq!("lpt".parse(), Ok(Restart::Fresh(InitialSolution::Lpt)));
+        assert!("reheat:-1".parse::<Restart>().is_err());
+        assert!("cool:0.5".parse::<Restart>().is_err());
+    }
+
+    #[test]
+    fn test_policy_is_due() {
+        let policy = RestartPolicy::new(Restart::Best, 100).with_max_restarts(2);
+
+        assert!(!policy.is_due(99, 0));
+        assert!(policy.is_due(100, 1));
+        assert!(!policy.is_due(100, 2));
+    }
+}
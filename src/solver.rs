@@ -0,0 +1,727 @@
+use crate::serializer::{JsonLines, JsonRecord, Record, Serializer};
+use crate::utils::{Assignment, Case, Incumbent, Schedule, Settings};
+use crate::{greedy, multistart, portfolio, random, simulated_annealing};
+use std::{fmt, io::Write, time::{Duration, SystemTime}};
+
+/// Limits of single solver run.
+/// Solver stops as soon as any of them is reached.
+#[derive(Clone, Debug)]
+pub struct Budget {
+    time: Duration,
+    cpu_time: Option<Duration>,
+    iterations: Option<u64>,
+    evaluations: Option<u64>,
+    incumbent: Option<Incumbent>,
+}
+
+impl Budget {
+    /// Creates budget limited by wall-clock `time`.
+    pub fn with_time(time: Duration) -> Self {
+        Self {
+            time,
+            cpu_time: None,
+            iterations: None,
+            evaluations: None,
+            incumbent: None,
+        }
+    }
+
+    /// Limits CPU time consumed by solver's thread.
+    pub fn with_cpu_time(mut self, cpu_time: Duration) -> Self {
+        self.cpu_time = Some(cpu_time);
+        self
+    }
+
+    /// Limits number of iterations.
+    pub fn with_iterations(mut self, iterations: u64) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    /// Limits number of evaluated neighbours.
+    pub fn with_evaluations(mut self, evaluations: u64) -> Self {
+        self.evaluations = Some(evaluations);
+        self
+    }
+
+    /// Makes solver share its best makespan through `incumbent`
+    /// and stop once incumbent proves optimality.
+    pub fn with_incumbent(mut self, incumbent: Incumbent) -> Self {
+        self.incumbent = Some(incumbent);
+        self
+    }
+
+    /// Returns wall-clock time limit.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Returns incumbent shared with other solvers.
+    pub fn incumbent(&self) -> Option<&Incumbent> {
+        self.incumbent.as_ref()
+    }
+
+    /// Returns limit reached by `usage`, if any.
+    pub fn exceeded(&self, usage: &Usage) -> Option<Termination> {
+        self.counted_out(usage.iterations, usage.evaluations).or_else(|| {
+            if self.cpu_time.is_some_and(|limit| usage.cpu_time >= limit) {
+                Some(Termination::CpuTime)
+            } else if usage.wall_time >= self.time {
+                Some(Termination::WallTime)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns limit reached by counters alone, which is cheaper to check than clocks.
+    pub fn counted_out(&self, iterations: u64, evaluations: u64) -> Option<Termination> {
+        if self.iterations.is_some_and(|limit| iterations >= limit) {
+            Some(Termination::Iterations)
+        } else if self.evaluations.is_some_and(|limit| evaluations >= limit) {
+            Some(Termination::Evaluations)
+        } else {
+            None
+        }
+    }
+
+    /// Returns used fraction of the most consumed limit.
+    pub fn fraction(&self, usage: &Usage) -> f64 {
+        let ratio = |used: f64, limit: f64| if limit > 0.0 { used / limit } else { 1.0 };
+        let mut fraction = ratio(usage.wall_time.as_secs_f64(), self.time.as_secs_f64());
+        if let Some(limit) = self.cpu_time {
+            fraction = fraction.max(ratio(usage.cpu_time.as_secs_f64(), limit.as_secs_f64()));
+        }
+        if let Some(limit) = self.iterations {
+            fraction = fraction.max(ratio(usage.iterations as f64, limit as f64));
+        }
+        if let Some(limit) = self.evaluations {
+            fraction = fraction.max(ratio(usage.evaluations as f64, limit as f64));
+        }
+        fraction
+    }
+}
+
+/// Resources used by solver so far.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Usage {
+    pub wall_time: Duration,
+    pub cpu_time: Duration,
+    pub iterations: u64,
+    pub evaluations: u64,
+}
+
+/// Reason why solver stopped.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Termination {
+    WallTime,
+    CpuTime,
+    Iterations,
+    Evaluations,
+    /// Temperature fell to final one.
+    Temperature,
+    /// Solution didn't change for too many iterations.
+    Stagnation,
+    /// Best makespan reached case's lower bound.
+    LowerBound,
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Self::WallTime => "wall-clock time limit",
+            Self::CpuTime => "CPU time limit",
+            Self::Iterations => "iteration limit",
+            Self::Evaluations => "evaluation limit",
+            Self::Temperature => "final temperature",
+            Self::Stagnation => "changeless iterations limit",
+            Self::LowerBound => "lower bound",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// State of iterative solver passed to observers along with events.
+pub struct Progress<'a> {
+    /// Number of iterations made so far.
+    pub iteration: u64,
+    pub temperature: f64,
+    pub makespan: u128,
+    pub best: u128,
+    /// Current solution, converted to schedule only by observers which need it.
+    /// It's `None` in events replayed from [`Log`] which didn't keep solutions.
+    pub solution: Option<&'a Assignment>,
+}
+
+/// Event in run of iterative solver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    /// Solver started, or resumed from checkpoint.
+    Started,
+    /// Proposed move was accepted or rejected, once per iteration.
+    Moved { accepted: bool },
+    /// Current solution is the best one so far, improving on `previous` makespan.
+    NewBest { previous: u128 },
+    /// Temperature was reduced from `previous` after full iteration.
+    TemperatureChanged { previous: f64 },
+    /// Search was restarted, `restarts` times so far.
+    Restarted { restarts: u32 },
+    /// Solver stopped.
+    Finished { termination: Termination },
+}
+
+impl Event {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Self::Started => EventKind::Started,
+            Self::Moved { .. } => EventKind::Moved,
+            Self::NewBest { .. } => EventKind::NewBest,
+            Self::TemperatureChanged { .. } => EventKind::TemperatureChanged,
+            Self::Restarted { .. } => EventKind::Restarted,
+            Self::Finished { .. } => EventKind::Finished,
+        }
+    }
+}
+
+/// Kind of [`Event`], regardless of its data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Started,
+    Moved,
+    NewBest,
+    TemperatureChanged,
+    Restarted,
+    Finished,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 6] = [
+        Self::Started,
+        Self::Moved,
+        Self::NewBest,
+        Self::TemperatureChanged,
+        Self::Restarted,
+        Self::Finished,
+    ];
+}
+
+/// How observer uses events of some kind, ordered from the least demanding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Interest {
+    /// Events are ignored.
+    Ignored,
+    /// Events are used without current solution.
+    Event,
+    /// Events are used together with current solution, e.g. to draw it.
+    Solution,
+}
+
+/// Interest of an observer in every kind of events, see [`Observer::interest`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interests([Interest; 6]);
+
+impl Interests {
+    pub fn of(observer: &dyn Observer) -> Self {
+        Self(EventKind::ALL.map(|kind| observer.interest(kind)))
+    }
+
+    pub fn get(&self, kind: EventKind) -> Interest {
+        self.0[kind as usize]
+    }
+}
+
+/// Every event is used without solution.
+impl Default for Interests {
+    fn default() -> Self {
+        Self([Interest::Event; 6])
+    }
+}
+
+/// Receives progress of a solver.
+pub trait Observer {
+    /// Called after every iteration with makespan of current solution.
+    fn record(&mut self, _: u64, _: u128) {}
+
+    /// Called with record made at `time`, earlier than now when [`Log`] is replayed.
+    fn record_at(&mut self, iteration: u64, makespan: u128, _: SystemTime) {
+        self.record(iteration, makespan);
+    }
+
+    /// Called with remarks about the run, e.g. chosen parameters.
+    fn comment(&mut self, _: &str) {}
+
+    /// Called by iterative solvers when `event` happens.
+    fn event(&mut self, _: Event, _: &Progress<'_>) {}
+
+    /// Tells how events of `kind` are used, so that solvers which replay their [`Log`]
+    /// keep only events (and solutions) which are needed.
+    fn interest(&self, _: EventKind) -> Interest {
+        Interest::Event
+    }
+
+    /// Called once solver has finished.
+    fn finish(&mut self) {}
+}
+
+impl<T: Write> Observer for Serializer<T> {
+    fn record(&mut self, iteration: u64, makespan: u128) {
+        self.add_record(Record::new(iteration, makespan));
+    }
+
+    fn comment(&mut self, comment: &str) {
+        self.add_comment(comment).unwrap();
+    }
+
+    fn interest(&self, _: EventKind) -> Interest {
+        Interest::Ignored
+    }
+
+    fn finish(&mut self) {
+        self.save("---\n").unwrap();
+    }
+}
+
+impl<T: Write> Observer for JsonLines<T> {
+    fn record(&mut self, iteration: u64, makespan: u128) {
+        self.record_at(iteration, makespan, SystemTime::now());
+    }
+
+    fn record_at(&mut self, iteration: u64, makespan: u128, time: SystemTime) {
+        let best = self.best.map_or(makespan, |best| best.min(makespan));
+        self.best = Some(best);
+        let mut record = JsonRecord::at(time, iteration, makespan, best);
+        if let Some((temperature, accepted)) = self.last_move.take() {
+            record.temperature = Some(temperature);
+            record.accepted = Some(accepted);
+        }
+        self.add_record(&record).unwrap();
+    }
+
+    fn comment(&mut self, comment: &str) {
+        self.add_comment(comment).unwrap();
+    }
+
+    fn event(&mut self, event: Event, progress: &Progress<'_>) {
+        if let Event::Moved { accepted } = event {
+            self.last_move = Some((progress.temperature, accepted));
+            // Resumed runs know best makespan found before they were interrupted.
+            self.best = Some(progress.best);
+        }
+    }
+
+    /// Moves complete records with temperature and acceptance.
+    fn interest(&self, kind: EventKind) -> Interest {
+        if kind == EventKind::Moved {
+            Interest::Event
+        } else {
+            Interest::Ignored
+        }
+    }
+
+    fn finish(&mut self) {
+        self.add_end().unwrap();
+    }
+}
+
+/// State of solver at logged event.
+#[derive(Debug, Clone)]
+struct Snapshot {
+    iteration: u64,
+    temperature: f64,
+    makespan: u128,
+    best: u128,
+    solution: Option<Box<Assignment>>,
+}
+
+#[derive(Debug, Clone)]
+enum LogEntry {
+    /// Record, with flag set when it improved on all previous records.
+    Record(Record, SystemTime, bool),
+    Comment(String),
+    Event(Event, Snapshot),
+}
+
+impl LogEntry {
+    /// Returns iteration of entry made every iteration or temperature step, which may be thinned out.
+    fn thinned_iteration(&self) -> Option<u64> {
+        match self {
+            Self::Record(record, _, false) => Some(record.iteration()),
+            Self::Event(Event::Moved { .. }, snapshot) | Self::Event(Event::TemperatureChanged { .. }, snapshot) => {
+                Some(snapshot.iteration)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Capacity of logs which parallel runs keep until it's known which of them wins, see [`Log::with_capacity`].
+pub const RUN_LOG_CAPACITY: usize = 50_000;
+
+/// Observer which keeps records, comments and events in memory,
+/// so they can be replayed to another observer later.
+/// Records keep time at which they were made.
+#[derive(Debug, Clone, Default)]
+pub struct Log {
+    entries: Vec<LogEntry>,
+    interests: Interests,
+    /// Limit of kept entries which are made every iteration or temperature step.
+    capacity: Option<usize>,
+    /// Such entries are kept only for iterations divisible by `2^thinning`.
+    thinning: u32,
+    thinned: usize,
+    best: Option<u128>,
+}
+
+impl Log {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps only events in which `interests` are taken, see [`Observer::interest`].
+    /// By default every event is kept, without solution.
+    /// Copy of current solution costs memory of a solution per event, so it should be kept for rare events only.
+    pub fn with_interests(mut self, interests: Interests) -> Self {
+        self.interests = interests;
+        self
+    }
+
+    /// Keeps at most `capacity` records and events made every iteration or temperature step (moves and temperature changes),
+    /// so that memory taken by long runs is bounded.
+    /// Once there are more of them, every other one is dropped and only those of every other iteration are kept from then on.
+    /// Records which improve on all previous ones, comments and other events are always kept.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity.max(1));
+        self
+    }
+
+    /// Passes everything logged so far to `observer` and finishes it.
+    pub fn replay(&self, observer: &mut dyn Observer) {
+        if self.thinning > 0 {
+            observer.comment(&format!("log keeps records of every {}th iteration", 1u64 << self.thinning));
+        }
+        for entry in &self.entries {
+            match entry {
+                LogEntry::Record(record, time, _) => observer.record_at(record.iteration(), record.makespan(), *time),
+                LogEntry::Comment(comment) => observer.comment(comment),
+                LogEntry::Event(event, snapshot) => observer.event(
+                    *event,
+                    &Progress {
+                        iteration: snapshot.iteration,
+                        temperature: snapshot.temperature,
+                        makespan: snapshot.makespan,
+                        best: snapshot.best,
+                        solution: snapshot.solution.as_deref(),
+                    },
+                ),
+            }
+        }
+        observer.finish();
+    }
+
+    /// Returns `true` if nothing was logged.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Tells whether `entry` is of iteration thinned out.
+    fn skips(&self, entry: &LogEntry) -> bool {
+        entry.thinned_iteration().is_some_and(|iteration| iteration % (1 << self.thinning) != 0)
+    }
+
+    fn push(&mut self, entry: LogEntry) {
+        if self.skips(&entry) {
+            return;
+        }
+        if entry.thinned_iteration().is_some() {
+            self.thinned += 1;
+        }
+        self.entries.push(entry);
+
+        while self.capacity.is_some_and(|capacity| self.thinned > capacity) {
+            self.thinning += 1;
+            let stride = 1 << self.thinning;
+            self.entries
+                .retain(|entry| entry.thinned_iteration().is_none_or(|iteration| iteration % stride == 0));
+            self.thinned = self.entries.iter().filter(|entry| entry.thinned_iteration().is_some()).count();
+        }
+    }
+}
+
+impl Observer for Log {
+    fn record(&mut self, iteration: u64, makespan: u128) {
+        self.record_at(iteration, makespan, SystemTime::now());
+    }
+
+    fn record_at(&mut self, iteration: u64, makespan: u128, time: SystemTime) {
+        let improved = self.best.is_none_or(|best| makespan < best);
+        if improved {
+            self.best = Some(makespan);
+        }
+        self.push(LogEntry::Record(Record::new(iteration, makespan), time, improved));
+    }
+
+    fn comment(&mut self, comment: &str) {
+        self.push(LogEntry::Comment(comment.to_string()));
+    }
+
+    fn event(&mut self, event: Event, progress: &Progress<'_>) {
+        let interest = self.interests.get(event.kind());
+        if interest == Interest::Ignored {
+            return;
+        }
+        let mut entry = LogEntry::Event(
+            event,
+            Snapshot {
+                iteration: progress.iteration,
+                temperature: progress.temperature,
+                makespan: progress.makespan,
+                best: progress.best,
+                solution: None,
+            },
+        );
+        // Solution isn't copied for entry which is thinned out right away.
+        if self.skips(&entry) {
+            return;
+        }
+        if let (Interest::Solution, LogEntry::Event(_, snapshot)) = (interest, &mut entry) {
+            snapshot.solution = progress.solution.cloned().map(Box::new);
+        }
+        self.push(entry);
+    }
+
+    fn interest(&self, kind: EventKind) -> Interest {
+        self.interests.get(kind)
+    }
+}
+
+/// Observer which ignores everything.
+impl Observer for () {
+    fn interest(&self, _: EventKind) -> Interest {
+        Interest::Ignored
+    }
+}
+
+/// Statistics of single solver run.
+#[derive(Debug, Clone, Default)]
+pub struct SolverStats {
+    pub iterations: u64,
+    pub elapsed: Duration,
+    /// Human readable details specific to solver, one per line.
+    pub details: Vec<String>,
+    /// Whether found schedule reaches case's lower bound, which proves it's optimal.
+    pub optimal: bool,
+    /// Limit which stopped iterative solver.
+    pub termination: Option<Termination>,
+}
+
+/// Schedule found by solver together with run statistics.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub schedule: Schedule,
+    pub stats: SolverStats,
+}
+
+/// Common interface of all scheduling algorithms.
+pub trait Solver: Send + Sync {
+    /// Returns name under which solver is registered.
+    fn name(&self) -> &'static str;
+
+    /// Schedules tasks of `case`.
+    /// Iterative solvers start from `initial` schedule if one is given
+    /// and stop when `budget` is exhausted.
+    fn solve(&self, case: &Case, initial: Option<Schedule>, budget: &Budget, observer: &mut dyn Observer) -> Outcome;
+}
+
+/// Builds solver configured with application settings.
+pub type SolverFactory = fn(&Settings) -> Result<Box<dyn Solver>, String>;
+
+/// All available solvers with their names.
+const REGISTRY: &[(&str, SolverFactory)] = &[
+    ("lpt", greedy::solver),
+    ("random", random::solver),
+    ("sa", simulated_annealing::solver),
+    ("multistart", multistart::solver),
+    ("portfolio", portfolio::solver),
+];
+
+/// Returns names of all registered solvers.
+pub fn names() -> Vec<&'static str> {
+    REGISTRY.iter().map(|(name, _)| *name).collect()
+}
+
+/// Creates solver registered under `name`.
+pub fn create(name: &str, settings: &Settings) -> Result<Box<dyn Solver>, String> {
+    REGISTRY
+        .iter()
+        .find(|(registered, _)| *registered == name)
+        .ok_or_else(|| format!("Unknown algorithm `{}`. Available: {}.", name, names().join(", ")))
+        .and_then(|(_, factory)| factory(settings))
+}
+
+#[cfg(test)]
+mod test_solver {
+    use super::*;
+    use crate::utils::Task;
+
+    #[test]
+    fn test_registered_solvers() {
+        let settings = Settings::default();
+        let mut case = Case::new().with_cores(3);
+        case.add_tasks((1..10).map(Task::with_length).collect());
+
+        for name in names() {
+            let solver = create(name, &settings).unwrap();
+            let outcome = solver.solve(&case, None, &Budget::with_time(Duration::from_secs(1)), &mut ());
+
+            assert_eq!(solver.name(), name);
+            assert_eq!(outcome.schedule.cores().len(), 3);
+            assert!(outcome.schedule.makespan().unwrap() >= case.lower_bound());
+        }
+    }
+
+    #[test]
+    fn test_budget_limits() {
+        let budget = Budget::with_time(Duration::from_secs(10))
+            .with_iterations(100)
+            .with_cpu_time(Duration::from_secs(2));
+        let usage = Usage {
+            wall_time: Duration::from_secs(1),
+            cpu_time: Duration::from_secs(1),
+            iterations: 20,
+            evaluations: 1000,
+        };
+
+        assert_eq!(budget.exceeded(&usage), None);
+        assert_eq!(budget.fraction(&usage), 0.5);
+        assert_eq!(budget.exceeded(&Usage { iterations: 100, ..usage }), Some(Termination::Iterations));
+        assert_eq!(
+            budget.exceeded(&Usage { cpu_time: Duration::from_secs(3), ..usage }),
+            Some(Termination::CpuTime)
+        );
+        assert_eq!(
+            budget.exceeded(&Usage { wall_time: Duration::from_secs(10), ..usage }),
+            Some(Termination::WallTime)
+        );
+        assert_eq!(budget.with_evaluations(1000).exceeded(&usage), Some(Termination::Evaluations));
+    }
+
+    #[test]
+    fn test_unknown_solver() {
+        assert!(create("exact", &Settings::default()).is_err());
+    }
+
+    #[test]
+    fn test_log_replay() {
+        let mut log = Log::new();
+        log.record(0, 10);
+        log.comment("restart");
+        log.record(1, 8);
+
+        let mut serializer = Serializer::new(Vec::new());
+        log.replay(&mut serializer);
+
+        let out = String::from_utf8(serializer.get_writer().to_owned()).unwrap();
+        assert_eq!(out, "0,10\n# restart\n1,8\n---\n");
+    }
+
+    #[test]
+    fn test_log_replay_events() {
+        let mut schedule = Schedule::new();
+        schedule.add_core(crate::utils::Core::from(vec![Task::with_length(8)]));
+        let solution = Assignment::from(&schedule);
+        let progress = Progress {
+            iteration: 4,
+            temperature: 2.0,
+            makespan: 8,
+            best: 8,
+            solution: Some(&solution),
+        };
+        let mut solutions = Vec::new();
+        let mut graphs = Interests::default();
+        graphs.0[EventKind::Moved as usize] = Interest::Solution;
+        let mut quiet = Interests::default();
+        quiet.0[EventKind::Started as usize] = Interest::Ignored;
+        for mut log in [Log::new(), Log::new().with_interests(graphs), Log::new().with_interests(quiet)] {
+            log.event(Event::Started, &progress);
+            log.event(Event::Moved { accepted: true }, &progress);
+            let mut hook = crate::observers::Hook(|event, progress: &Progress<'_>| {
+                assert_eq!(progress.iteration, 4);
+                solutions.push((event, progress.solution.is_some()));
+            });
+            log.replay(&mut hook);
+        }
+
+        assert_eq!(
+            solutions,
+            vec![
+                (Event::Started, false),
+                (Event::Moved { accepted: true }, false),
+                (Event::Started, false),
+                (Event::Moved { accepted: true }, true),
+                (Event::Moved { accepted: true }, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_log_thins_out_frequent_entries() {
+        let mut log = Log::new().with_capacity(4);
+        log.record(0, 100);
+        for iteration in 1..=16 {
+            log.record(iteration, 200 - iteration as u128);
+        }
+        log.record(17, 50);
+        log.comment("done");
+
+        let mut serializer = Serializer::new(Vec::new());
+        log.replay(&mut serializer);
+
+        // Four frequent records fit, improving records and comments are kept regardless.
+        let out = String::from_utf8(serializer.get_writer().to_owned()).unwrap();
+        assert_eq!(out, "# log keeps records of every 4th iteration\n0,100\n4,196\n8,192\n12,188\n16,184\n17,50\n# done\n---\n");
+    }
+
+    #[test]
+    fn test_log_replay_keeps_record_times() {
+        let mut log = Log::new();
+        log.record_at(1, 10, std::time::UNIX_EPOCH + Duration::from_secs(5));
+
+        let mut json = JsonLines::new(Vec::new());
+        log.replay(&mut json);
+
+        let out = String::from_utf8(json.get_writer().to_owned()).unwrap();
+        let record: serde_json::Value = serde_json::from_str(out.lines().next().unwrap()).unwrap();
+        assert_eq!(record["timestamp"], 5.0);
+    }
+
+    #[test]
+    fn test_json_lines_complete_records_with_moves() {
+        let mut schedule = Schedule::new();
+        schedule.add_core(crate::utils::Core::from(vec![Task::with_length(8)]));
+        let solution = Assignment::from(&schedule);
+        let progress = Progress {
+            iteration: 1,
+            temperature: 3.0,
+            makespan: 8,
+            best: 7,
+            solution: Some(&solution),
+        };
+        let mut log = JsonLines::new(Vec::new());
+
+        log.record(0, 10);
+        log.event(Event::Moved { accepted: false }, &progress);
+        log.record(2, 8);
+        log.finish();
+
+        let out = String::from_utf8(log.get_writer().to_owned()).unwrap();
+        let lines: Vec<serde_json::Value> = out.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines[0]["temperature"], serde_json::Value::Null);
+        assert_eq!(lines[0]["best"], 10);
+        // Iterations are the same as in CSV log.
+        assert_eq!(lines[1]["iteration"], 2);
+        assert_eq!(lines[1]["temperature"], 3.0);
+        assert_eq!(lines[1]["accepted"], false);
+        assert_eq!(lines[1]["best"], 7);
+        assert_eq!(lines[2]["type"], "end");
+    }
+}
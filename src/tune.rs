@@ -0,0 +1,361 @@
+use crate::config::{integer, number, AnnealingConfig};
+use crate::multistart::InitialSolution;
+use crate::simulated_annealing::{Neighbourhood, Reduction, SimulatedAnnealingParams, Solution};
+use crate::solver::Budget;
+use crate::utils::Case;
+use rand::{seq::index, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::{error::Error, fmt, fs, str::FromStr, thread, time::Duration};
+use yaml_rust::{Yaml, YamlLoader};
+
+/// Values tried for every tuned annealing parameter.
+/// Parameters with no values aren't tuned and keep their default values.
+///
+/// # Example
+///
+/// ```yaml
+/// tune:
+///   initial_temperature: [25, 75, 150]
+///   final_temperature: [0.01]
+///   cooling: [geometric:0.99, geometric:0.995, lam:0.999]
+///   iterations_per_temperature: [10, 20, 50]
+///   operators: ["biased:0.6", "biased:0.6=3,swap,ejection:3"]
+/// ```
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct SearchSpace {
+    pub initial_temperature: Vec<f64>,
+    pub final_temperature: Vec<f64>,
+    pub cooling: Vec<Reduction>,
+    pub iterations_per_temperature: Vec<u16>,
+    pub operators: Vec<Neighbourhood>,
+}
+
+impl SearchSpace {
+    /// Space searched when none is given.
+    pub fn standard() -> Self {
+        Self {
+            initial_temperature: vec![10.0, 75.0, 300.0],
+            final_temperature: vec![0.01, 0.1],
+            cooling: vec![
+                Reduction::Geometric(0.99),
+                Reduction::Geometric(0.995),
+                Reduction::Geometric(0.999),
+                Reduction::Lam(0.999),
+            ],
+            iterations_per_temperature: vec![10, 20, 50],
+            operators: ["biased:0.6", "biased:0.6=3,swap,two-opt,ejection:3", "biased:0.8=2,swap=2,random"]
+                .iter()
+                .map(|operators| operators.parse().unwrap())
+                .collect(),
+        }
+    }
+
+    /// Parses `tune` section of YAML file.
+    pub fn from_yaml(source: &str) -> Result<Self, String> {
+        let documents = YamlLoader::load_from_str(source).map_err(|err| err.to_string())?;
+        let mut space = Self::default();
+
+        let section = match documents.first().map(|document| &document["tune"]) {
+            Some(Yaml::Hash(section)) => section,
+            _ => return Err("Search space has to be given as `tune` mapping.".to_string()),
+        };
+
+        for (key, values) in section {
+            let key = key.as_str().ok_or("Search space keys have to be strings.")?;
+            let values = values
+                .as_vec()
+                .ok_or_else(|| format!("`{}` has to be a list of values.", key))?;
+            match key {
+                "initial_temperature" => space.initial_temperature = each(values, |value| number(key, value))?,
+                "final_temperature" => space.final_temperature = each(values, |value| number(key, value))?,
+                "cooling" => space.cooling = each(values, |value| parsed(key, value))?,
+                "iterations_per_temperature" => {
+                    space.iterations_per_temperature = each(values, |value| integer(key, value))?
+                }
+                "operators" => space.operators = each(values, |value| parsed(key, value))?,
+                _ => return Err(format!("Unknown tuned parameter `{}`.", key)),
+            }
+        }
+
+        Ok(space)
+    }
+
+    pub fn read_from_file<P: Into<String>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let source = fs::read_to_string(path.into())?;
+        Ok(Self::from_yaml(&source)?)
+    }
+
+    /// Returns number of all configurations in space.
+    pub fn size(&self) -> usize {
+        self.dimensions().iter().product()
+    }
+
+    /// Returns up to `count` distinct configurations, chosen at random when space is bigger.
+    pub fn sample(&self, count: usize, rng: &mut ChaCha12Rng) -> Vec<AnnealingConfig> {
+        let size = self.size();
+        if size <= count {
+            (0..size).map(|idx| self.configuration(idx)).collect()
+        } else {
+            let mut chosen = index::sample(rng, size, count).into_vec();
+            chosen.sort_unstable();
+            chosen.into_iter().map(|idx| self.configuration(idx)).collect()
+        }
+    }
+
+    fn dimensions(&self) -> [usize; 5] {
+        [
+            self.initial_temperature.len().max(1),
+            self.final_temperature.len().max(1),
+            self.cooling.len().max(1),
+            self.iterations_per_temperature.len().max(1),
+            self.operators.len().max(1),
+        ]
+    }
+
+    /// Decodes `idx`-th configuration of space, i.e. `idx` written in mixed radix of dimensions.
+    fn configuration(&self, mut idx: usize) -> AnnealingConfig {
+        let mut digits = [0; 5];
+        for (digit, dimension) in digits.iter_mut().zip(&self.dimensions()) {
+            *digit = idx % dimension;
+            idx /= dimension;
+        }
+
+        AnnealingConfig {
+            initial_temperature: self.initial_temperature.get(digits[0]).copied(),
+            final_temperature: self.final_temperature.get(digits[1]).copied(),
+            cooling: self.cooling.get(digits[2]).cloned(),
+            iterations_per_temperature: self.iterations_per_temperature.get(digits[3]).copied(),
+            operators: self.operators.get(digits[4]).cloned(),
+            ..AnnealingConfig::default()
+        }
+    }
+}
+
+fn each<T>(values: &[Yaml], parse: impl Fn(&Yaml) -> Result<T, String>) -> Result<Vec<T>, String> {
+    values.iter().map(parse).collect()
+}
+
+fn parsed<T: FromStr<Err = String>>(key: &str, value: &Yaml) -> Result<T, String> {
+    value
+        .as_str()
+        .ok_or_else(|| format!("Values of `{}` have to be strings.", key))?
+        .parse()
+}
+
+/// Configuration evaluated in single round of tuning.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub config: AnnealingConfig,
+    /// Mean relative gap between found makespans and lower bounds of instances.
+    pub gap: f64,
+}
+
+/// Outcome of single round of successive halving.
+#[derive(Debug, Clone)]
+pub struct Round {
+    pub round: usize,
+    pub seeds: usize,
+    /// Evaluated candidates, the best one first.
+    pub candidates: Vec<Candidate>,
+}
+
+impl fmt::Display for Round {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Round {}: {} candidates, {} seeds per instance, best gap {:.4}%",
+            self.round,
+            self.candidates.len(),
+            self.seeds,
+            self.candidates[0].gap * 100.0
+        )
+    }
+}
+
+/// Tunes annealing parameters by successive halving.
+///
+/// Every round evaluates surviving candidates on all instances, each with the same seeds
+/// (one run per seed), and keeps the better half of them.
+/// Number of seeds doubles every round, so the last candidates are compared most accurately.
+/// Tuning ends once single candidate is left.
+///
+/// Candidates run concurrently, so every run is limited by number of evaluated neighbours
+/// rather than by time, which would depend on how many runs share the processor.
+pub struct Tuner {
+    instances: Vec<Case>,
+    space: SearchSpace,
+    candidates: usize,
+    seeds: usize,
+    seed: u64,
+    evaluations: u64,
+}
+
+impl Tuner {
+    /// Creates tuner of 16 candidates from `space`, starting with 2 seeds and 100000 evaluations per run.
+    pub fn new(instances: Vec<Case>, space: SearchSpace) -> Self {
+        Self {
+            instances,
+            space,
+            candidates: 16,
+            seeds: 2,
+            seed: 0,
+            evaluations: 100_000,
+        }
+    }
+
+    /// Sets number of candidates sampled from search space (at least one).
+    pub fn with_candidates(mut self, candidates: usize) -> Self {
+        self.candidates = candidates.max(1);
+        self
+    }
+
+    /// Sets number of seeds used in the first round (at least one).
+    pub fn with_seeds(mut self, seeds: usize) -> Self {
+        self.seeds = seeds.max(1);
+        self
+    }
+
+    /// Sets seed from which candidates and seeds of runs are derived.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets number of neighbours evaluated by single run (at least one).
+    pub fn with_evaluations(mut self, evaluations: u64) -> Self {
+        self.evaluations = evaluations.max(1);
+        self
+    }
+
+    /// Runs tuning, reporting every finished round, and returns the best configuration.
+    pub fn run(&self, mut report: impl FnMut(&Round)) -> AnnealingConfig {
+        let mut configs = self.space.sample(self.candidates, &mut ChaCha12Rng::seed_from_u64(self.seed));
+        let mut seeds = self.seeds;
+
+        for round in 1.. {
+            if configs.len() == 1 {
+                break;
+            }
+
+            let mut candidates: Vec<Candidate> = thread::scope(|scope| {
+                let handles: Vec<_> = configs
+                    .iter()
+                    .map(|config| scope.spawn(move || self.evaluate(config, seeds)))
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .zip(&configs)
+                    .map(|(handle, config)| Candidate {
+                        config: config.clone(),
+                        gap: handle.join().expect("Tuning run panicked."),
+                    })
+                    .collect()
+            });
+            // Stable sort keeps earlier sampled candidate first among equal ones.
+            candidates.sort_by(|first, second| first.gap.total_cmp(&second.gap));
+
+            let round = Round { round, seeds, candidates };
+            report(&round);
+
+            let survivors = round.candidates.len().div_ceil(2);
+            configs = round.candidates.into_iter().take(survivors).map(|candidate| candidate.config).collect();
+            seeds *= 2;
+        }
+
+        configs.swap_remove(0)
+    }
+
+    /// Returns mean relative gap of runs with `config` over all instances and `seeds` seeds.
+    fn evaluate(&self, config: &AnnealingConfig, seeds: usize) -> f64 {
+        let budget = Budget::with_time(Duration::MAX).with_evaluations(self.evaluations);
+        let mut gaps = 0.0;
+        for case in &self.instances {
+            let lower_bound = case.lower_bound().max(1) as f64;
+            for seed in (0..seeds as u64).map(|run| self.seed.wrapping_add(run)) {
+                let params = SimulatedAnnealingParams {
+                    initial_solution: InitialSolution::Random.build(case, &mut ChaCha12Rng::seed_from_u64(seed)),
+                    seed,
+                    ..config.apply(SimulatedAnnealingParams::default())
+                };
+                let makespan = Solution::new(params).with_budget(budget.clone()).run(&mut ()).makespan().unwrap() as f64;
+                gaps += (makespan - lower_bound) / lower_bound;
+            }
+        }
+        gaps / (self.instances.len() * seeds).max(1) as f64
+    }
+}
+
+#[cfg(test)]
+mod test_tune {
+    use super::*;
+    use crate::utils::Task;
+
+    #[test]
+    fn test_space_from_yaml() {
+        let space = SearchSpace::from_yaml(
+            "tune:\n  initial_temperature: [10, 50.5]\n  cooling: [geometric:0.9, lam:0.99]\n  operators: [swap]\n",
+        )
+        .unwrap();
+
+        assert_eq!(space.initial_temperature, vec![10.0, 50.5]);
+        assert_eq!(space.cooling, vec![Reduction::Geometric(0.9), Reduction::Lam(0.99)]);
+        assert_eq!(space.size(), 4);
+        assert!(SearchSpace::from_yaml("tune:\n  cooling: geometric:0.9\n").is_err());
+        assert!(SearchSpace::from_yaml("tune:\n  temperature: [1]\n").is_err());
+        assert!(SearchSpace::from_yaml("annealing:\n  cooling: geometric:0.9\n").is_err());
+    }
+
+    #[test]
+    fn test_sample_is_distinct() {
+        let space = SearchSpace::standard();
+        let sampled = space.sample(20, &mut ChaCha12Rng::seed_from_u64(1));
+
+        assert_eq!(sampled.len(), 20);
+        for (idx, config) in sampled.iter().enumerate() {
+            assert!(!sampled[idx + 1..].contains(config));
+        }
+        assert_eq!(space.sample(space.size() + 5, &mut ChaCha12Rng::seed_from_u64(1)).len(), space.size());
+    }
+
+    #[test]
+    fn test_halving_picks_candidate() {
+        let mut case = Case::new().with_cores(3);
+        case.add_tasks((1..20).map(Task::with_length).collect());
+        let space = SearchSpace {
+            initial_temperature: vec![5.0, 20.0],
+            iterations_per_temperature: vec![5, 10],
+            cooling: vec![Reduction::Geometric(0.9)],
+            ..SearchSpace::default()
+        };
+
+        let mut rounds = Vec::new();
+        let best = Tuner::new(vec![case], space)
+            .with_candidates(3)
+            .with_evaluations(2000)
+            .run(|round| rounds.push((round.candidates.len(), round.seeds)));
+
+        assert_eq!(rounds, vec![(3, 2), (2, 4)]);
+        assert_eq!(best.cooling, Some(Reduction::Geometric(0.9)));
+    }
+
+    #[test]
+    fn test_evaluation_is_repeatable() {
+        // 7 tasks of length 3 on 2 cores can't do better than makespan 12, above lower bound 11,
+        // so no run stops early and each uses whole budget.
+        let mut case = Case::new().with_cores(2);
+        case.add_tasks(vec![Task::with_length(3); 7]);
+        let tuner = Tuner::new(vec![case], SearchSpace::standard()).with_evaluations(500);
+        let config = AnnealingConfig {
+            initial_temperature: Some(2.0),
+            ..AnnealingConfig::default()
+        };
+
+        let gaps: Vec<f64> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..4).map(|_| scope.spawn(|| tuner.evaluate(&config, 3))).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        assert!(gaps.iter().all(|gap| *gap == gaps[0]));
+    }
+}
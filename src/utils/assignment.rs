@@ -0,0 +1,270 @@
+use super::{Core, LoadHeap, Schedule, Task};
+use serde::{Deserialize, Serialize};
+
+/// Single change of an assignment.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Move {
+    /// Moves `task` from core `from` to core `to`.
+    Transfer { task: usize, from: usize, to: usize },
+    /// Exchanges cores of two tasks scheduled on different cores.
+    Swap { first: usize, second: usize },
+}
+
+impl Move {
+    /// Returns move which reverts this one.
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Transfer { task, from, to } => Self::Transfer { task, from: to, to: from },
+            Self::Swap { .. } => self,
+        }
+    }
+}
+
+/// Compact representation of schedule used by iterative solvers.
+///
+/// Tasks are identified by their index and every task knows its core,
+/// so moves can be applied and undone without copying timelines.
+/// Makespan after a move is evaluated in `O(1)` using heap of core loads.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Assignment {
+    lengths: Vec<u64>,
+    core_of: Vec<usize>,
+    /// Tasks scheduled on every core, in no particular order.
+    timelines: Vec<Vec<usize>>,
+    /// Position of every task in its core's timeline.
+    slot: Vec<usize>,
+    loads: LoadHeap,
+}
+
+impl Assignment {
+    /// Returns number of cores.
+    pub fn cores(&self) -> usize {
+        self.timelines.len()
+    }
+
+    /// Returns number of tasks.
+    pub fn tasks(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// Returns length of `task`.
+    pub fn length(&self, task: usize) -> u64 {
+        self.lengths[task]
+    }
+
+    /// Returns core on which `task` is scheduled.
+    pub fn core_of(&self, task: usize) -> usize {
+        self.core_of[task]
+    }
+
+    /// Returns tasks scheduled on `core`.
+    pub fn tasks_on(&self, core: usize) -> &[usize] {
+        &self.timelines[core]
+    }
+
+    /// Returns loads of cores.
+    pub fn loads(&self) -> &LoadHeap {
+        &self.loads
+    }
+
+    /// Returns makespan (0 when there are no cores).
+    pub fn makespan(&self) -> u128 {
+        self.loads.max_load()
+    }
+
+    /// Returns makespan which assignment would have after applying `mv`, without applying it.
+    pub fn makespan_after(&self, mv: Move) -> u128 {
+        match mv {
+            Move::Transfer { task, from, to } => {
+                let length = self.lengths[task] as u128;
+                let from_load = self.loads.load(from) - length;
+                let to_load = self.loads.load(to) + length;
+                self.loads
+                    .max_load_excluding(&[from, to])
+                    .max(from_load)
+                    .max(to_load)
+            }
+            Move::Swap { first, second } => {
+                let (first_core, second_core) = (self.core_of[first], self.core_of[second]);
+                let (first, second) = (self.lengths[first] as u128, self.lengths[second] as u128);
+                let first_load = self.loads.load(first_core) - first + second;
+                let second_load = self.loads.load(second_core) - second + first;
+                self.loads
+                    .max_load_excluding(&[first_core, second_core])
+                    .max(first_load)
+                    .max(second_load)
+            }
+        }
+    }
+
+    /// Applies `mv`.
+    pub fn apply(&mut self, mv: Move) {
+        match mv {
+            Move::Transfer { task, from, to } => {
+                debug_assert_eq!(self.core_of[task], from);
+                self.detach(task);
+                self.attach(task, to);
+            }
+            Move::Swap { first, second } => {
+                let (first_core, second_core) = (self.core_of[first], self.core_of[second]);
+                debug_assert_ne!(first_core, second_core);
+                self.detach(first);
+                self.detach(second);
+                self.attach(first, second_core);
+                self.attach(second, first_core);
+            }
+        }
+    }
+
+    /// Reverts `mv` previously applied to assignment.
+    pub fn undo(&mut self, mv: Move) {
+        self.apply(mv.reversed());
+    }
+
+    /// Builds schedule with the same tasks on the same cores.
+    pub fn to_schedule(&self) -> Schedule {
+        let mut schedule = Schedule::new();
+        for timeline in &self.timelines {
+            let tasks: Vec<Task> = timeline
+                .iter()
+                .map(|&task| Task::with_length(self.lengths[task]))
+                .collect();
+            schedule.add_core(Core::from(tasks));
+        }
+        schedule
+    }
+
+    fn detach(&mut self, task: usize) {
+        let core = self.core_of[task];
+        let slot = self.slot[task];
+        self.timelines[core].swap_remove(slot);
+        if let Some(&moved) = self.timelines[core].get(slot) {
+            self.slot[moved] = slot;
+        }
+        let load = self.loads.load(core) - self.lengths[task] as u128;
+        self.loads.set(core, load);
+    }
+
+    fn attach(&mut self, task: usize, core: usize) {
+        self.core_of[task] = core;
+        self.slot[task] = self.timelines[core].len();
+        self.timelines[core].push(task);
+        let load = self.loads.load(core) + self.lengths[task] as u128;
+        self.loads.set(core, load);
+    }
+}
+
+impl From<&Schedule> for Assignment {
+    fn from(schedule: &Schedule) -> Self {
+        let mut lengths = Vec::new();
+        let mut core_of = Vec::new();
+        let mut slot = Vec::new();
+        let mut timelines = Vec::new();
+
+        for (core, tasks) in schedule.cores().iter().map(Core::get_tasks).enumerate() {
+            let mut timeline = Vec::with_capacity(tasks.len());
+            for task in tasks {
+                slot.push(timeline.len());
+                timeline.push(lengths.len());
+                lengths.push(task.length());
+                core_of.push(core);
+            }
+            timelines.push(timeline);
+        }
+
+        let loads = LoadHeap::new(schedule.cores().iter().map(Core::working_time).collect());
+
+        Self {
+            lengths,
+            core_of,
+            timelines,
+            slot,
+            loads,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_assignment {
+    use super::*;
+
+    fn schedule() -> Schedule {
+        let mut schedule = Schedule::new();
+        for lengths in &[vec![5, 3], vec![4], vec![1, 1, 1]] {
+            schedule.add_core(Core::from(lengths.iter().map(|&l| Task::with_length(l)).collect::<Vec<_>>()));
+        }
+        schedule
+    }
+
+    #[test]
+    fn test_from_schedule() {
+        let assignment = Assignment::from(&schedule());
+
+        assert_eq!(assignment.cores(), 3);
+        assert_eq!(assignment.tasks(), 6);
+        assert_eq!(assignment.tasks_on(0), &[0, 1]);
+        assert_eq!(assignment.core_of(5), 2);
+        assert_eq!(assignment.makespan(), 8);
+        assert_eq!(assignment.to_schedule(), schedule());
+    }
+
+    #[test]
+    fn test_makespan_after_matches_apply() {
+        let mut assignment = Assignment::from(&schedule());
+        let mv = Move::Transfer { task: 1, from: 0, to: 2 };
+
+        let predicted = assignment.makespan_after(mv);
+        assignment.apply(mv);
+
+        assert_eq!(predicted, 6);
+        assert_eq!(assignment.makespan(), 6);
+        assert_eq!(assignment.core_of(1), 2);
+        assert_eq!(assignment.tasks_on(0), &[0]);
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut assignment = Assignment::from(&schedule());
+        let mv = Move::Swap { first: 0, second: 2 };
+
+        let predicted = assignment.makespan_after(mv);
+        assignment.apply(mv);
+
+        assert_eq!(predicted, 7);
+        assert_eq!(assignment.makespan(), 7);
+        assert_eq!((assignment.core_of(0), assignment.core_of(2)), (1, 0));
+
+        assignment.undo(mv);
+        assert_eq!(assignment.makespan(), 8);
+        assert_eq!((assignment.core_of(0), assignment.core_of(2)), (0, 1));
+    }
+
+    #[test]
+    fn test_undo() {
+        let mut assignment = Assignment::from(&schedule());
+        let moves = [
+            Move::Transfer { task: 0, from: 0, to: 1 },
+            Move::Transfer { task: 3, from: 2, to: 0 },
+            Move::Transfer { task: 0, from: 1, to: 2 },
+        ];
+
+        for mv in &moves {
+            assignment.apply(*mv);
+        }
+        for mv in moves.iter().rev() {
+            assignment.undo(*mv);
+        }
+
+        let mut loads: Vec<u128> = assignment
+            .to_schedule()
+            .cores()
+            .iter()
+            .map(Core::working_time)
+            .collect();
+        loads.sort_unstable();
+
+        assert_eq!(assignment.makespan(), 8);
+        assert_eq!(loads, vec![3, 4, 8]);
+        assert_eq!(assignment.loads().loads(), &[8, 4, 3]);
+    }
+}
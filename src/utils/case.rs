@@ -1,7 +1,8 @@
+use super::schedule::Schedule;
 use super::task::Task;
 
 /// Represents single test case consisting of cores count and list of Tasks to be scheduled.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Default)]
 pub struct Case {
     cores: u64,
     tasks: Vec<Task>,
@@ -77,6 +78,39 @@ impl Case {
     pub fn tasks(&self) -> Vec<Task> {
         self.tasks.clone()
     }
+
+    /// Returns lower bound of makespan for this case,
+    /// i.e. greater of `ceil(sum / cores)` and the longest task length.
+    /// No schedule can be shorter, so reaching it proves optimality.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut case = Case::new().with_cores(2);
+    /// case.add_tasks(vec![Task::with_length(3), Task::with_length(4)]);
+    /// assert_eq!(case.lower_bound(), 4);
+    /// ```
+    pub fn lower_bound(&self) -> u128 {
+        if self.cores == 0 {
+            return 0;
+        }
+        let sum: u128 = self.tasks.iter().map(|task| task.length() as u128).sum();
+        let longest = self.tasks.iter().map(|task| task.length() as u128).max().unwrap_or(0);
+        let cores = self.cores as u128;
+
+        longest.max(sum.div_ceil(cores))
+    }
+}
+
+/// Creates case with the same cores and tasks as `schedule`.
+impl From<&Schedule> for Case {
+    fn from(schedule: &Schedule) -> Self {
+        let mut case = Case::new().with_cores(schedule.cores().len() as u64);
+        for core in schedule.cores() {
+            case.add_tasks(core.get_tasks());
+        }
+        case
+    }
 }
 
 #[cfg(test)]
@@ -107,7 +141,7 @@ mod test_case {
     #[test]
     fn test_add_tasks() {
         let mut case = Case::new();
-        let tasks: Vec<Task> = (0..9).map(|l| Task::with_length(l)).collect();
+        let tasks: Vec<Task> = (0..9).map(Task::with_length).collect();
 
         case.add_tasks(tasks);
     }
@@ -129,4 +163,18 @@ mod test_case {
 
         assert_eq!(lengths, vec![3, 5, 8]);
     }
+
+    #[test]
+    fn test_lower_bound() {
+        let mut case = Case::new().with_cores(3);
+        case.add_tasks((1..6).map(Task::with_length).collect());
+        // sum = 15, so every core has to work at least 5.
+        assert_eq!(case.lower_bound(), 5);
+
+        case.add_task(Task::with_length(9));
+        // longest task dominates ceil(24 / 3) = 8.
+        assert_eq!(case.lower_bound(), 9);
+
+        assert_eq!(Case::new().lower_bound(), 0);
+    }
 }
@@ -1,8 +1,9 @@
 use crate::utils::task::Task;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Represent single core (processor) on which task times are scheduled.
-#[derive(Clone, PartialEq, Debug, Serialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(from = "SerializedCore")]
 pub struct Core {
     #[serde(rename = "tasks")]
     timeline: Vec<Task>,
@@ -47,6 +48,18 @@ impl<T> From<T> for Core where T: Into<Vec<Task>> {
     }
 }
 
+/// Serialized form of core, from which working time is recomputed.
+#[derive(Deserialize)]
+struct SerializedCore {
+    tasks: Vec<Task>,
+}
+
+impl From<SerializedCore> for Core {
+    fn from(core: SerializedCore) -> Self {
+        Self::from(core.tasks)
+    }
+}
+
 #[cfg(test)]
 mod test_core {
     use super::*;
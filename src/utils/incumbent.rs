@@ -0,0 +1,76 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Best makespan found so far, shared between concurrently running solvers.
+///
+/// Cloning `Incumbent` yields handle to the same shared value.
+#[derive(Clone, Debug)]
+pub struct Incumbent {
+    best: Arc<AtomicU64>,
+    lower_bound: u128,
+}
+
+impl Incumbent {
+    /// Creates new Incumbent with no known solution
+    /// for case which makespan can't be lower than `lower_bound`.
+    pub fn new(lower_bound: u128) -> Self {
+        Self {
+            best: Arc::new(AtomicU64::new(u64::MAX)),
+            lower_bound,
+        }
+    }
+
+    /// Reports makespan of found solution.
+    /// Shared value is updated only if `makespan` is better than current one.
+    pub fn offer(&self, makespan: u128) {
+        let makespan = makespan.min(u64::MAX as u128) as u64;
+        self.best.fetch_min(makespan, Ordering::Relaxed);
+    }
+
+    /// Returns best makespan reported so far (`u128::MAX` if none was reported).
+    pub fn best(&self) -> u128 {
+        match self.best.load(Ordering::Relaxed) {
+            u64::MAX => u128::MAX,
+            best => best as u128,
+        }
+    }
+
+    /// Returns lower bound of makespan for solved case.
+    pub fn lower_bound(&self) -> u128 {
+        self.lower_bound
+    }
+
+    /// Returns `true` when best reported makespan reached lower bound,
+    /// so no solver can improve it anymore.
+    pub fn is_optimal(&self) -> bool {
+        self.best() <= self.lower_bound
+    }
+}
+
+#[cfg(test)]
+mod test_incumbent {
+    use super::*;
+
+    #[test]
+    fn test_create_empty() {
+        let incumbent = Incumbent::new(10);
+        assert_eq!(incumbent.best(), u128::MAX);
+        assert!(!incumbent.is_optimal());
+    }
+
+    #[test]
+    fn test_offer_keeps_minimum() {
+        let incumbent = Incumbent::new(10);
+        let shared = incumbent.clone();
+
+        shared.offer(15);
+        incumbent.offer(20);
+        assert_eq!(incumbent.best(), 15);
+        assert!(!incumbent.is_optimal());
+
+        shared.offer(10);
+        assert!(incumbent.is_optimal());
+    }
+}
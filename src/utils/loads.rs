@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+
+/// Binary heap of core indices which knows position of every core,
+/// so load of any core can be changed in `O(log m)`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct IndexedHeap {
+    order: Vec<usize>,
+    position: Vec<usize>,
+    descending: bool,
+}
+
+impl IndexedHeap {
+    fn new(loads: &[u128], descending: bool) -> Self {
+        let mut heap = Self {
+            order: (0..loads.len()).collect(),
+            position: (0..loads.len()).collect(),
+            descending,
+        };
+        for idx in (0..loads.len() / 2).rev() {
+            heap.sift_down(loads, idx);
+        }
+        heap
+    }
+
+    fn precedes(&self, loads: &[u128], first: usize, second: usize) -> bool {
+        if self.descending {
+            loads[first] > loads[second]
+        } else {
+            loads[first] < loads[second]
+        }
+    }
+
+    fn swap(&mut self, first: usize, second: usize) {
+        self.order.swap(first, second);
+        self.position[self.order[first]] = first;
+        self.position[self.order[second]] = second;
+    }
+
+    fn sift_up(&mut self, loads: &[u128], mut idx: usize) -> usize {
+        while idx > 0 {
+            let parent = (idx - 1) / 2;
+            if !self.precedes(loads, self.order[idx], self.order[parent]) {
+                break;
+            }
+            self.swap(idx, parent);
+            idx = parent;
+        }
+        idx
+    }
+
+    fn sift_down(&mut self, loads: &[u128], mut idx: usize) {
+        loop {
+            let mut top = idx;
+            for child in &[2 * idx + 1, 2 * idx + 2] {
+                if *child < self.order.len() && self.precedes(loads, self.order[*child], self.order[top]) {
+                    top = *child;
+                }
+            }
+            if top == idx {
+                break;
+            }
+            self.swap(idx, top);
+            idx = top;
+        }
+    }
+
+    /// Restores heap order after load of `core` has changed.
+    fn update(&mut self, loads: &[u128], core: usize) {
+        let idx = self.sift_up(loads, self.position[core]);
+        self.sift_down(loads, idx);
+    }
+
+    /// Returns first core in heap order which isn't `excluded`.
+    ///
+    /// k-th element of heap order lies at depth lower than k,
+    /// so only first `2^(k + 1) - 1` entries have to be checked for k excluded cores.
+    fn top_excluding(&self, loads: &[u128], excluded: &[usize]) -> Option<usize> {
+        let depth = excluded.len() as u32 + 1;
+        let checked = (2usize.pow(depth) - 1).min(self.order.len());
+        self.order[..checked]
+            .iter()
+            .filter(|core| !excluded.contains(core))
+            .fold(None, |top: Option<usize>, &core| match top {
+                Some(top) if !self.precedes(loads, core, top) => Some(top),
+                _ => Some(core),
+            })
+    }
+}
+
+/// Loads of all cores together with max- and min-heaps over them.
+///
+/// Most loaded and least loaded core can be read in `O(1)`,
+/// changing load of single core costs `O(log m)`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LoadHeap {
+    loads: Vec<u128>,
+    max: IndexedHeap,
+    min: IndexedHeap,
+}
+
+impl LoadHeap {
+    /// Creates heap from loads of consecutive cores.
+    pub fn new(loads: Vec<u128>) -> Self {
+        let max = IndexedHeap::new(&loads, true);
+        let min = IndexedHeap::new(&loads, false);
+        Self { loads, max, min }
+    }
+
+    /// Returns number of cores.
+    pub fn len(&self) -> usize {
+        self.loads.len()
+    }
+
+    /// Returns `true` if there are no cores.
+    pub fn is_empty(&self) -> bool {
+        self.loads.is_empty()
+    }
+
+    /// Returns load of `core`.
+    pub fn load(&self, core: usize) -> u128 {
+        self.loads[core]
+    }
+
+    /// Returns loads of all cores.
+    pub fn loads(&self) -> &[u128] {
+        &self.loads
+    }
+
+    /// Sets load of `core`.
+    pub fn set(&mut self, core: usize, load: u128) {
+        self.loads[core] = load;
+        self.max.update(&self.loads, core);
+        self.min.update(&self.loads, core);
+    }
+
+    /// Returns most loaded core.
+    pub fn max_core(&self) -> Option<usize> {
+        self.max.order.first().copied()
+    }
+
+    /// Returns least loaded core.
+    pub fn min_core(&self) -> Option<usize> {
+        self.min.order.first().copied()
+    }
+
+    /// Returns highest load (0 when there are no cores).
+    pub fn max_load(&self) -> u128 {
+        self.max_core().map_or(0, |core| self.loads[core])
+    }
+
+    /// Returns highest load among cores other than `excluded` (0 when there are none).
+    pub fn max_load_excluding(&self, excluded: &[usize]) -> u128 {
+        self.max
+            .top_excluding(&self.loads, excluded)
+            .map_or(0, |core| self.loads[core])
+    }
+
+    /// Returns least loaded core other than `excluded`.
+    pub fn min_core_excluding(&self, excluded: &[usize]) -> Option<usize> {
+        self.min.top_excluding(&self.loads, excluded)
+    }
+}
+
+#[cfg(test)]
+mod test_load_heap {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_create() {
+        let heap = LoadHeap::new(vec![4, 9, 1, 7]);
+
+        assert_eq!(heap.max_core(), Some(1));
+        assert_eq!(heap.min_core(), Some(2));
+        assert_eq!(heap.max_load(), 9);
+        assert_eq!(LoadHeap::new(Vec::new()).max_load(), 0);
+    }
+
+    #[test]
+    fn test_excluding_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut heap = LoadHeap::new((0..13).map(|_| rng.gen_range(0..50)).collect());
+
+        for _ in 0..500 {
+            let core = rng.gen_range(0..heap.len());
+            heap.set(core, rng.gen_range(0..50));
+
+            let first = rng.gen_range(0..heap.len());
+            let second = rng.gen_range(0..heap.len());
+            let others = || (0..heap.len()).filter(|core| *core != first && *core != second);
+
+            assert_eq!(heap.max_load(), *heap.loads().iter().max().unwrap());
+            assert_eq!(
+                heap.max_load_excluding(&[first, second]),
+                others().map(|core| heap.load(core)).max().unwrap()
+            );
+            assert_eq!(
+                heap.min_core_excluding(&[first]).map(|core| heap.load(core)),
+                (0..heap.len()).filter(|core| *core != first).map(|core| heap.load(core)).min()
+            );
+        }
+    }
+}
@@ -2,11 +2,19 @@ pub mod macros;
 pub mod case;
 pub mod core;
 pub mod task;
+pub mod incumbent;
+pub mod clock;
+pub mod loads;
+pub mod assignment;
 pub mod schedule;
 pub mod settings;
 
 pub use self::case::Case;
 pub use self::core::Core;
 pub use self::task::Task;
+pub use self::incumbent::Incumbent;
+pub use self::clock::CpuClock;
+pub use self::loads::LoadHeap;
+pub use self::assignment::{Assignment, Move};
 pub use self::schedule::Schedule;
 pub use self::settings::Settings;
\ No newline at end of file
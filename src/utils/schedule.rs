@@ -1,9 +1,9 @@
 use std::error::Error;
 
-use super::Core;
-use serde::Serialize;
+use super::{Case, Core};
+use serde::{Deserialize, Serialize};
 use serde_json::to_string as json;
-#[derive(Clone, PartialEq, Debug, Serialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Schedule {
     cores: Vec<Core>,
 }
@@ -33,6 +33,43 @@ impl Schedule {
     pub fn serialize(&self) -> String {
         json(self).unwrap()
     }
+
+    /// Checks that schedule assigns exactly tasks of `case` to its cores.
+    pub fn validate(&self, case: &Case) -> Result<(), String> {
+        if self.cores.len() as u64 != case.cores() {
+            return Err(format!(
+                "Schedule has {} cores, but case has {}.",
+                self.cores.len(),
+                case.cores()
+            ));
+        }
+
+        let mut scheduled: Vec<u64> = self
+            .cores
+            .iter()
+            .flat_map(Core::get_tasks)
+            .map(|task| task.length())
+            .collect();
+        let mut expected: Vec<u64> = case.tasks().iter().map(|task| task.length()).collect();
+        scheduled.sort_unstable();
+        expected.sort_unstable();
+
+        if scheduled.len() != expected.len() {
+            return Err(format!(
+                "Schedule's {} tasks differ from case's {} tasks.",
+                scheduled.len(),
+                expected.len()
+            ));
+        }
+        // Both are sorted, so the smaller length at first difference is the one too many on its side.
+        match scheduled.iter().zip(&expected).find(|(scheduled, expected)| scheduled != expected) {
+            Some((scheduled, expected)) if scheduled < expected => {
+                Err(format!("Schedule has extra task of length {}.", scheduled))
+            }
+            Some((_, expected)) => Err(format!("Schedule is missing task of length {}.", expected)),
+            None => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -83,4 +120,34 @@ mod test_schedule {
 
         assert_eq!(makespan, 8);
     }
+
+    #[test]
+    fn test_deserialize() {
+        let schedule: Schedule = serde_json::from_str(r#"{"cores":[{"tasks":[3,5]},{"tasks":[]}]}"#).unwrap();
+
+        assert_eq!(schedule.cores().len(), 2);
+        assert_eq!(schedule.cores()[0].working_time(), 8);
+        assert_eq!(serde_json::from_str::<Schedule>(&schedule.serialize()).unwrap(), schedule);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut schedule = Schedule::new();
+        schedule.add_core(Core::from(vec![Task::with_length(3), Task::with_length(5)]));
+        schedule.add_core(Core::from(vec![Task::with_length(3)]));
+        let mut case = Case::new().with_cores(2);
+        case.add_tasks(vec![Task::with_length(5), Task::with_length(3), Task::with_length(3)]);
+
+        assert!(schedule.validate(&case).is_ok());
+        assert!(schedule.validate(&Case::from(&schedule).with_cores(3)).is_err());
+        case.add_task(Task::with_length(1));
+        assert!(schedule.validate(&case).is_err());
+
+        let mut case = Case::new().with_cores(2);
+        case.add_tasks(vec![Task::with_length(5), Task::with_length(3), Task::with_length(4)]);
+        assert_eq!(schedule.validate(&case), Err("Schedule has extra task of length 3.".to_string()));
+        let mut case = Case::new().with_cores(2);
+        case.add_tasks(vec![Task::with_length(5), Task::with_length(3), Task::with_length(2)]);
+        assert_eq!(schedule.validate(&case), Err("Schedule is missing task of length 2.".to_string()));
+    }
 }
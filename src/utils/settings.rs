@@ -1,30 +1,63 @@
+use crate::config::AnnealingConfig;
+use crate::instances::CaseFormat;
+use crate::io::ScheduleFormat;
+use crate::multistart::InitialSolution;
+use crate::pretty_print::TaskLabels;
+use crate::serializer::LogFormat;
 use once_cell::sync::OnceCell;
-use std::sync::RwLock;
+use std::{sync::RwLock, time::Duration};
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Settings {
     pub prompt: bool,
     pub unbuffered: bool,
     pub verbosity: u8,
+    /// What is printed inside tasks of graphs.
+    pub task_labels: TaskLabels,
     pub input_files: Vec<String>,
+    /// Format of case files, told by their extension when `None`.
+    pub case_format: Option<CaseFormat>,
     pub log_file: String,
+    pub log_format: LogFormat,
+    /// File (or directory, when several cases are solved) to which found schedules are written.
+    pub output: Option<String>,
+    pub format: ScheduleFormat,
+    /// Limit of wall-clock time of solving single case, in seconds.
     pub kill_time: u16,
+    /// Limit of CPU time of every solver's thread.
+    pub cpu_time: Option<Duration>,
+    /// Limit of iterations of iterative solvers.
+    pub max_iterations: Option<u64>,
+    /// Limit of neighbours evaluated by iterative solvers.
+    pub max_evaluations: Option<u64>,
+    /// Seed of every random generator used while solving.
+    pub seed: u64,
+    /// Schedule from which iterative solvers start.
+    pub initial: Option<String>,
+    /// File to which iterative solvers periodically save their state.
+    pub checkpoint: Option<String>,
+    /// Minimal wall-clock time between two checkpoints.
+    pub checkpoint_interval: Duration,
+    /// Checkpoint from which iterative solvers continue.
+    pub resume: Option<String>,
+    /// Name of algorithm used to solve cases.
+    pub algorithm: String,
+    /// Names of algorithms raced by portfolio.
+    pub portfolio: Vec<String>,
+    /// Number of parallel annealing runs.
+    pub runs: usize,
+    /// Initial solutions assigned to parallel runs.
+    pub starts: Vec<InitialSolution>,
+    /// Annealing parameters overriding the default ones, including calibration and restarts.
+    pub annealing: AnnealingConfig,
 }
 
 static SETTINGS: OnceCell<RwLock<Settings>> = OnceCell::new();
 
 impl Settings {
-    pub fn init(prompt: bool, unbuffered: bool, verbosity: u8, files: Vec<String>, log_file: String, kill_time: u16) {
-        match SETTINGS.set(RwLock::new(Settings {
-            prompt,
-            unbuffered,
-            verbosity,
-            input_files: files,
-            log_file,
-            kill_time,
-        })) {
-            _ => return,
-        }
+    pub fn init(settings: Settings) {
+        // Settings are initialized only once, subsequent calls are ignored.
+        let _ = SETTINGS.set(RwLock::new(settings));
     }
 
     pub fn get() -> Result<&'static RwLock<Settings>, ()> {
@@ -1,9 +1,9 @@
 use rand::Rng;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 /// Represents single, indivisible task, which takes `length` time to complete.
-#[derive(PartialEq, Debug, Clone, Copy, Serialize)]
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Task (u64);
 
 impl Task {
@@ -20,20 +20,21 @@ impl Task {
         Self(length)
     }
 
-    /// Creates new Task object with random length from range [min; max),
+    /// Creates new Task object with length drawn by `rng` from range [min; max),
     /// i.e. inclusive of `min` and exclusive of `max`.
     ///
     /// # Example
     ///
     /// ```
-    /// let task = Task::from_range(1..64);
+    /// let task = Task::from_range(1..64, &mut StdRng::seed_from_u64(0));
     /// assert!(task.length() > 0 && task.length() < 64);
     /// ```
-    pub fn from_range(range: Range<u64>) -> Self {
-        Self(rand::thread_rng().gen_range(range))
+    pub fn from_range<R: Rng>(range: Range<u64>, rng: &mut R) -> Self {
+        Self(rng.gen_range(range))
     }
 
     /// Returns Task length.
+    #[allow(clippy::needless_return)]
     pub fn length(&self) -> u64
     {
         return self.0;
@@ -43,6 +44,7 @@ impl Task {
 #[cfg(test)]
 mod test_task {
     use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_create_with_length() {
@@ -52,8 +54,9 @@ mod test_task {
 
     #[test]
     fn test_create_from_range() {
-        let task = Task::from_range(1..64);
+        let task = Task::from_range(1..64, &mut StdRng::seed_from_u64(0));
         assert!(task.length() > 0 && task.length() < 64);
+        assert_eq!(task, Task::from_range(1..64, &mut StdRng::seed_from_u64(0)));
     }
 
     #[test]